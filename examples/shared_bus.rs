@@ -0,0 +1,50 @@
+// Sharing one blocking I2C bus between the display and another device with embedded-hal-bus.
+// Runs on the host with a bus that prints the transactions instead of a real one:
+// cargo run --example shared_bus --features blocking
+//
+// On hardware, replace `PrintBus` with the I2C instance of the HAL.
+// For multiple tasks or interrupts, use `CriticalSectionDevice` or `AtomicDevice` instead of `RefCellDevice`.
+
+use core::cell::RefCell;
+
+use akafugu_twidisplay_async::*;
+use embedded_hal::i2c::{ErrorKind, ErrorType, I2c, Operation};
+use embedded_hal_bus::i2c::RefCellDevice;
+
+/// I2C bus printing the writes and reading zeros
+struct PrintBus;
+
+impl ErrorType for PrintBus {
+    type Error = ErrorKind;
+}
+
+impl I2c for PrintBus {
+    fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Self::Error> {
+        for operation in operations {
+            match operation {
+                Operation::Write(bytes) => println!("{:#04x} write {:02x?}", address, bytes),
+                Operation::Read(buffer) => buffer.fill(0),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn main() -> Result<(), Error<ErrorKind>> {
+    let bus = RefCell::new(PrintBus);
+
+    let mut akafugu = TWIDisplayBlocking::new(RefCellDevice::new(&bus), DEFAULT_ADDRESS);
+    let mut sensor = RefCellDevice::new(&bus);
+
+    akafugu.clear_display()?;
+
+    for _ in 0..3 {
+        // another device on the same bus, e.g. a temperature sensor
+        let mut reading = [0u8; 2];
+        sensor.write_read(0x48, &[0x00], &mut reading).map_err(Error::I2C)?;
+
+        akafugu.display_temperature(reading[0] as i16, TempUnits::Celsius, None, None)?;
+    }
+
+    Ok(())
+}
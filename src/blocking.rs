@@ -0,0 +1,367 @@
+//! Blocking version of the driver, for HALs implementing only the blocking `embedded-hal` traits.
+//!
+//! `TWIDisplayBlocking` wraps the async driver around an adapter for the blocking I2C bus,
+//! so that both versions share the same command logic. The methods are the same,
+//! minus `.await`, and the delays implement the blocking `DelayNs` trait.
+
+use super::*;
+
+use core::future::Future;
+use core::pin::pin;
+use core::task::{Context, Poll, Waker};
+
+use embedded_hal::delay::DelayNs as BlockingDelayNs;
+use embedded_hal::i2c::{ErrorType, I2c as BlockingI2cBus, Operation as I2cOperation};
+
+/// Async I2C bus completing every transaction right away on a blocking bus
+#[derive(Debug, Default)]
+struct BlockingI2c<I2C>(I2C);
+
+impl<I2C: ErrorType> ErrorType for BlockingI2c<I2C> {
+    type Error = I2C::Error;
+}
+
+impl<I2C: BlockingI2cBus> I2c for BlockingI2c<I2C> {
+    async fn transaction(&mut self, address: u8, operations: &mut [I2cOperation<'_>]) -> Result<(), Self::Error> {
+        self.0.transaction(address, operations)
+    }
+}
+
+/// Async delay waiting on a blocking delay
+struct BlockingDelay<'a, D>(&'a mut D);
+
+impl<D: BlockingDelayNs> DelayNs for BlockingDelay<'_, D> {
+    async fn delay_ns(&mut self, ns: u32) {
+        self.0.delay_ns(ns)
+    }
+}
+
+/// Run a future of the async driver to completion.
+/// With the blocking bus and delays above it never has to wait, so it's ready on the first poll.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+// forwards the methods without delays to the async driver,
+// the return type is the `Ok` type of the result, `()` if not given
+macro_rules! forward {
+    ($(fn $name:ident($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)?;)*) => {
+        $(
+            #[doc = concat!("Blocking version of [`TWIDisplay::", stringify!($name), "()`]")]
+            pub fn $name(&mut self, $($arg: $ty),*) -> Result<forward!(@ret $($ret)?), Error<E>> {
+                block_on(self.inner.$name($($arg),*))
+            }
+        )*
+    };
+    (@ret) => { () };
+    (@ret $ret:ty) => { $ret };
+}
+
+/// TWIDisplay driver for blocking I2C buses
+#[derive(Debug, Default)]
+pub struct TWIDisplayBlocking<I2C> {
+    inner: TWIDisplay<BlockingI2c<I2C>>,
+}
+
+impl<I2C, E> TWIDisplayBlocking<I2C>
+where
+    I2C: BlockingI2cBus<Error = E>,
+{
+    /// Create a new instance of the blocking TWIDisplay driver.
+    pub fn new(i2c: I2C, dev_addr: u8) -> Self {
+        TWIDisplayBlocking {
+            inner: TWIDisplay::new(BlockingI2c(i2c), dev_addr),
+        }
+    }
+
+    /// Blocking version of [`TWIDisplay::try_new()`]
+    pub fn try_new(i2c: I2C, dev_addr: u8) -> Result<Self, (Error<E>, I2C)> {
+        match block_on(TWIDisplay::try_new(BlockingI2c(i2c), dev_addr)) {
+            Ok(inner) => Ok(TWIDisplayBlocking { inner }),
+            Err((error, i2c)) => Err((error, i2c.0)),
+        }
+    }
+
+    /// Create a new instance of the blocking TWIDisplay driver for a unit with the given number of digits.
+    pub fn new_with_digits(i2c: I2C, dev_addr: u8, digits: u8) -> Self {
+        TWIDisplayBlocking {
+            inner: TWIDisplay::new_with_digits(BlockingI2c(i2c), dev_addr, digits),
+        }
+    }
+
+    /// Destroy driver instance, return I2C bus instance.
+    pub fn destroy(self) -> I2C {
+        self.inner.destroy().0
+    }
+
+    /// Borrow the I2C bus instance, e.g. to inspect its configuration
+    pub fn i2c_ref(&self) -> &I2C {
+        &self.inner.i2c_ref().0
+    }
+
+    /// Borrow the I2C bus instance mutably, e.g. to change its frequency,
+    /// keeping the state of the driver (unlike `destroy()`)
+    pub fn i2c_mut(&mut self) -> &mut I2C {
+        &mut self.inner.i2c_mut().0
+    }
+
+    /// Get the I2C address the driver talks to
+    pub fn address(&self) -> u8 {
+        self.inner.address()
+    }
+
+    /// Get the number of digits the driver uses
+    pub fn digits(&self) -> u8 {
+        self.inner.digits()
+    }
+
+    /// Get the last brightness set with `set_brightness()`
+    pub fn brightness(&self) -> u8 {
+        self.inner.brightness()
+    }
+
+    /// Get the last mode set with `set_mode()`
+    pub fn mode(&self) -> Mode {
+        self.inner.mode()
+    }
+
+    /// Blocking version of [`TWIDisplay::is_present()`]
+    pub fn is_present(&mut self) -> bool {
+        block_on(self.inner.is_present())
+    }
+
+    /// Blocking version of [`TWIDisplay::set_invert()`]
+    pub fn set_invert(&mut self, invert: bool) {
+        self.inner.set_invert(invert)
+    }
+
+    /// Blocking version of [`TWIDisplay::set_orientation()`]
+    pub fn set_orientation(&mut self, flip: bool) {
+        self.inner.set_orientation(flip)
+    }
+
+    /// Blocking version of [`TWIDisplay::begin_frame()`]
+    pub fn begin_frame(&mut self) {
+        self.inner.begin_frame()
+    }
+
+    /// Blocking version of [`TWIDisplay::set_rollback_on_error()`]
+    pub fn set_rollback_on_error(&mut self, enabled: bool) {
+        self.inner.set_rollback_on_error(enabled)
+    }
+
+    /// Blocking version of [`TWIDisplay::clear_display_confirmed()`]
+    pub fn clear_display_confirmed<D: BlockingDelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        block_on(self.inner.clear_display_confirmed(&mut BlockingDelay(delay)))
+    }
+
+    /// Blocking version of [`TWIDisplay::brightness_flash()`]
+    pub fn brightness_flash<D: BlockingDelayNs>(
+        &mut self,
+        peak: u8,
+        duration_ms: u32,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        block_on(self.inner.brightness_flash(peak, duration_ms, &mut BlockingDelay(delay)))
+    }
+
+    /// Blocking version of [`TWIDisplay::blink()`]
+    pub fn blink<D: BlockingDelayNs>(&mut self, times: u8, on_ms: u32, off_ms: u32, delay: &mut D) -> Result<(), Error<E>> {
+        block_on(self.inner.blink(times, on_ms, off_ms, &mut BlockingDelay(delay)))
+    }
+
+    /// Blocking version of [`TWIDisplay::fade_brightness()`]
+    pub fn fade_brightness<D: BlockingDelayNs>(
+        &mut self,
+        from: u8,
+        to: u8,
+        steps: u16,
+        step_ms: u32,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        block_on(self.inner.fade_brightness(from, to, steps, step_ms, &mut BlockingDelay(delay)))
+    }
+
+    /// Blocking version of [`TWIDisplay::scroll_text()`]
+    pub fn scroll_text<D: BlockingDelayNs>(&mut self, text: &str, delay: &mut D, step_ms: u32) -> Result<(), Error<E>> {
+        block_on(self.inner.scroll_text(text, &mut BlockingDelay(delay), step_ms))
+    }
+
+    /// Blocking version of [`TWIDisplay::scroll_range()`]
+    pub fn scroll_range<D: BlockingDelayNs>(
+        &mut self,
+        low: i16,
+        high: i16,
+        unit: TempUnits,
+        delay: &mut D,
+        step_ms: u32,
+    ) -> Result<(), Error<E>> {
+        block_on(self.inner.scroll_range(low, high, unit, &mut BlockingDelay(delay), step_ms))
+    }
+
+    /// Blocking version of [`TWIDisplay::play_frames()`]
+    pub fn play_frames<D: BlockingDelayNs>(
+        &mut self,
+        frames: &[[u8; 4]],
+        frame_ms: u32,
+        repeat: bool,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        block_on(self.inner.play_frames(frames, frame_ms, repeat, &mut BlockingDelay(delay)))
+    }
+
+    /// Blocking version of [`TWIDisplay::demo()`]
+    pub fn demo<D: BlockingDelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        block_on(self.inner.demo(&mut BlockingDelay(delay)))
+    }
+
+    /// Blocking version of [`TWIDisplay::self_test()`]
+    pub fn self_test<D: BlockingDelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        block_on(self.inner.self_test(&mut BlockingDelay(delay)))
+    }
+
+    forward! {
+        fn init(config: Config);
+        fn reset();
+        fn send_raw(bytes: &[u8]);
+        fn get_firmware_rev() -> u8;
+        fn get_number_digits() -> u8;
+        fn detect_digits() -> u8;
+        fn clear_display();
+        fn set_address(address: u8);
+        fn set_address_unchecked(address: u8);
+        fn change_address_now(address: u8);
+        fn reset_to_default_address();
+        fn display_address();
+        fn set_brightness(brightness: u8);
+        fn set_brightness_percent(percent: u8);
+        fn display_dots(dots: [bool; 4]);
+        fn set_colon(on: bool);
+        fn set_dot(position: u8, on: bool);
+        fn seconds_pip(seconds: u8);
+        fn send_digit(number: u8);
+        fn send_hex_digit(value: u8);
+        fn display_digit(position: impl Into<Position>, digit: u8);
+        fn display_two_digits(start_position: impl Into<Position>, value: u8);
+        fn display_digits(digits: [u8; 4]);
+        fn display_chars(chars: [char; 4]);
+        fn display_hex_digit(position: impl Into<Position>, value: u8);
+        fn display_hex(value: u16);
+        fn display_number(number: u16);
+        fn display_long_number(number: u32);
+        fn display_word(value: u16);
+        fn display_number_saturating(number: u32);
+        fn display_number_padded(number: u16, leading_zeros: bool);
+        fn display_number_aligned(number: u16, align: Alignment);
+        fn display_clamped(value: i32, overflow_glyph: char);
+        fn display_signed(number: i16);
+        fn display_float(value: f32, decimals: u8);
+        fn display_fixed(integer: i16, fraction: u8, dot_position: u8);
+        fn send_char(ch: impl Into<DisplayChar>);
+        fn display_char(position: impl Into<Position>, ch: impl Into<DisplayChar>);
+        fn clear_digit(position: impl Into<Position>);
+        fn send_text(text: &str);
+        fn send_text_upper(text: &str);
+        fn send_text_sanitized(text: &str);
+        fn display_code(code: [char; 4]);
+        fn redraw_text(text: &str);
+        fn display_text_fixed(text: &str);
+        fn display_static_text(text: &str);
+        fn display_number_with_dots(number: u16, dots: [bool; 4]);
+        fn display_text_with_dots(text: &str, dots: [bool; 4]);
+        fn flush(buf: &DisplayBuffer);
+        fn commit_frame();
+        fn redraw();
+        fn restore();
+        fn define_custom_char(slot: impl Into<Position>, segments: u8);
+        fn write_frame(segments: &[u8; 4]);
+        fn display_time(hours: u8, minutes: u8, dot: bool);
+        fn display_time_native(hours: u8, minutes: u8);
+        fn display_time_padded(hours: u8, minutes: u8, dot: bool, leading_zero: bool);
+        fn display_time_12h(hours: u8, minutes: u8, dot: bool);
+        fn display_clock_tuple(hms: (u8, u8, u8), dot: bool);
+        fn display_clock_toggle(hours: u8, minutes: u8, seconds: u8, show_seconds: bool);
+        fn display_stopwatch(minutes: u8, seconds: u8, dot: bool);
+        fn display_countdown(total_seconds: u16, dot: bool);
+        fn display_duration(seconds: u32);
+        fn display_date(month: u8, day: u8, format: DateFormat, dot: bool);
+        fn display_date_checked(year: u16, month: u8, day: u8, format: DateFormat, dot: bool);
+        fn display_year(year: u16);
+        fn set_mode(mode: Mode);
+        fn display_temperature(
+            temperature: i16,
+            unit: TempUnits,
+            lo_thresh: Option<i16>,
+            hi_thresh: Option<i16>,
+        );
+        fn display_temperature_hyst(
+            temperature: i16,
+            unit: TempUnits,
+            lo_thresh: Option<i16>,
+            hi_thresh: Option<i16>,
+            hysteresis: u8,
+        );
+        fn display_temperature_clamped(
+            temperature: i16,
+            unit: TempUnits,
+            lo_thresh: Option<i16>,
+            hi_thresh: Option<i16>,
+            behavior: ClampBehavior,
+        );
+        fn display_temperature_with_trend(temperature: i16, unit: TempUnits, trend: Trend);
+        fn display_temperature_converted(
+            celsius: i16,
+            display_as: TempUnits,
+            lo_thresh: Option<i16>,
+            hi_thresh: Option<i16>,
+        );
+        fn display_temperature_decimal(tenths: i16, unit: TempUnits);
+        fn display_value_with_unit(value: i16, unit: char, lo_thresh: Option<i16>, hi_thresh: Option<i16>);
+        fn display_value_unit2(value: u8, unit: [char; 2]);
+        fn display_percentage(percent: u8);
+        fn display_octet(value: u8);
+        fn display_battery(percent: u8);
+        fn display_bar(percent: u8);
+        fn spinner_frame(position: impl Into<Position>, frame: u8);
+        fn display_humidity(humidity: i16, lo_thresh: Option<i16>, hi_thresh: Option<i16>);
+    }
+}
+
+impl IdleDimmer {
+    /// Blocking version of [`IdleDimmer::tick()`]
+    pub fn tick_blocking<I2C, E>(
+        &mut self,
+        display: &mut TWIDisplayBlocking<I2C>,
+        elapsed_ms: u32,
+    ) -> Result<(), Error<E>>
+    where
+        I2C: BlockingI2cBus<Error = E>,
+    {
+        block_on(self.tick(&mut display.inner, elapsed_ms))
+    }
+
+    /// Blocking version of [`IdleDimmer::activity()`]
+    pub fn activity_blocking<I2C, E>(&mut self, display: &mut TWIDisplayBlocking<I2C>) -> Result<(), Error<E>>
+    where
+        I2C: BlockingI2cBus<Error = E>,
+    {
+        block_on(self.activity(&mut display.inner))
+    }
+}
+
+impl DisplayWriter {
+    /// Blocking version of [`DisplayWriter::flush()`]
+    pub fn flush_blocking<I2C, E>(&mut self, display: &mut TWIDisplayBlocking<I2C>) -> Result<(), Error<E>>
+    where
+        I2C: BlockingI2cBus<Error = E>,
+    {
+        block_on(self.flush(&mut display.inner))
+    }
+}
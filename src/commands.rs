@@ -0,0 +1,167 @@
+//! Encoding of the commands sent to the display, kept apart from the I2C transactions.
+//!
+//! These are pure functions returning the bytes to write, the driver methods only send them.
+
+use crate::{Mode, Register};
+
+/// Clear the display
+pub(crate) fn encode_clear() -> [u8; 1] {
+    [Register::CLEAR_DISPLAY]
+}
+
+/// Set the I2C address, active after a power cycle
+pub(crate) fn encode_address(address: u8) -> [u8; 2] {
+    [Register::I2C_ADDRESS_SETTING, address]
+}
+
+/// Show the current I2C address on the display
+pub(crate) fn encode_display_address() -> [u8; 1] {
+    [Register::DISPLAY_ADDRESS]
+}
+
+/// Set the brightness
+pub(crate) fn encode_brightness(brightness: u8) -> [u8; 2] {
+    [Register::BRIGHTNESS_SETTING, brightness]
+}
+
+/// Set the display mode
+pub(crate) fn encode_mode(mode: Mode) -> [u8; 2] {
+    match mode {
+        Mode::Rotate => [Register::MODE_SETTING, 0],
+        Mode::Scroll => [Register::MODE_SETTING, 1],
+    }
+}
+
+// dots are numbered 1,2,3,4 from the left, and they correspond to bits
+// so 0b0000_0010 is bit 1, dot 1, 0b0000_1000 is bit 3, dot 3 and so on
+// bit 0 is not one of the four dots, so dot index 0 (DOT_LEFT) is bit 1:
+// DOT_LEFT 0b0000_0010, DOT_CENTER_LEFT 0b0000_0100, DOT_CENTER_RIGHT 0b0000_1000, DOT_RIGHT 0b0001_0000
+
+// NOT VERIFIED ON A DISPLAY WITH A COLON: bit 0 is expected to be the center colon,
+// the four dots don't use it
+
+/// Bit of the dots register for the center colon
+pub(crate) const COLON_BIT: u8 = 0b0000_0001;
+
+/// Bit of the dots register for the dot at position 0-3
+pub(crate) fn dot_bit(position: u8) -> u8 {
+    2_u8.pow(position as u32 + 1_u32)
+}
+
+/// Dots register value, with boolean switches (true is on, false is off)
+pub(crate) fn dots_mask(dots: [bool; 4]) -> u8 {
+    let mut dotvalues: u8 = 0;
+
+    for (idx, dot) in dots.iter().enumerate() {
+        if *dot {
+            dotvalues |= dot_bit(idx as u8)
+        }
+    }
+    dotvalues
+}
+
+/// Set the dots register
+pub(crate) fn encode_dots(dotvalues: u8) -> [u8; 2] {
+    [Register::DOTS, dotvalues]
+}
+
+/// Show a number with the DISPLAY_WORD command, rendered by the firmware
+pub(crate) fn encode_word(value: u16) -> [u8; 3] {
+    let [high, low] = value.to_be_bytes();
+    [Register::DISPLAY_WORD, high, low]
+}
+
+/// Show the time with the DISPLAY_TIME command, rendered by the firmware
+pub(crate) fn encode_time(hours: u8, minutes: u8) -> [u8; 3] {
+    [Register::DISPLAY_TIME, hours, minutes]
+}
+
+/// Write a character or digit at a position
+pub(crate) fn encode_position(position: u8, ch: u8) -> [u8; 3] {
+    [Register::POSITION_SETTING, position, ch]
+}
+
+/// Write a raw segment mask at a position
+pub(crate) fn encode_custom_char(position: u8, segments: u8) -> [u8; 3] {
+    [Register::CUSTOM_CHAR, position, segments]
+}
+
+/// Helper function to get digits from a 4-digit number
+pub(crate) fn get_digits(number: u16) -> [u8; 4] {
+    let mut data = number;
+    let mut digits = [0u8; 4];
+    digits[0] = (data / 1000) as u8;
+    data %= 1000;
+    digits[1] = (data / 100) as u8;
+    data %= 100;
+    digits[2] = (data / 10) as u8;
+    data %= 10;
+    digits[3] = data as u8;
+    digits
+}
+
+/// Helper function to get the digits of a number up to 99999999, for 8-digit units
+pub(crate) fn get_long_digits(number: u32) -> [u8; 8] {
+    let mut data = number;
+    let mut digits = [0u8; 8];
+
+    for digit in digits.iter_mut().rev() {
+        *digit = (data % 10) as u8;
+        data /= 10;
+    }
+    digits
+}
+
+/// Helper function to replace leading zeros with spaces, the last digit is always kept
+pub(crate) fn blank_leading_zeros(digits: [u8; 4]) -> [u8; 4] {
+    let mut chars = digits;
+
+    for ch in chars.iter_mut().take(3) {
+        if *ch != 0 {
+            break;
+        }
+        *ch = b' ';
+    }
+    chars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dots_map_to_bits_1_to_4() {
+        assert_eq!(dots_mask([true, false, true, false]), 0b0000_1010);
+        assert_eq!(encode_dots(dots_mask([true, false, true, false])), [0x85, 0b0000_1010]);
+        assert_eq!(dots_mask([false; 4]), 0);
+        assert_eq!(dots_mask([true; 4]) & COLON_BIT, 0);
+    }
+
+    #[test]
+    fn position_and_custom_char_commands() {
+        assert_eq!(encode_position(2, b'P'), [0x89, 2, b'P']);
+        assert_eq!(encode_custom_char(1, 0b0100_0000), [0x84, 1, 0b0100_0000]);
+    }
+
+    #[test]
+    fn register_commands() {
+        assert_eq!(encode_clear(), [0x82]);
+        assert_eq!(encode_address(0x20), [0x81, 0x20]);
+        assert_eq!(encode_display_address(), [0x90]);
+        assert_eq!(encode_brightness(127), [0x80, 127]);
+        assert_eq!(encode_mode(Mode::Rotate), [0x83, 0]);
+        assert_eq!(encode_mode(Mode::Scroll), [0x83, 1]);
+        assert_eq!(encode_time(12, 34), [0x87, 12, 34]);
+        assert_eq!(encode_word(1234), [0x88, 0x04, 0xd2]);
+    }
+
+    #[test]
+    fn digits_of_numbers() {
+        assert_eq!(get_digits(1234), [1, 2, 3, 4]);
+        assert_eq!(get_digits(7), [0, 0, 0, 7]);
+        assert_eq!(get_long_digits(12345678), [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(blank_leading_zeros([0, 0, 4, 2]), [b' ', b' ', 4, 2]);
+        assert_eq!(blank_leading_zeros([0, 0, 0, 0]), [b' ', b' ', b' ', 0]);
+        assert_eq!(blank_leading_zeros([1, 0, 0, 0]), [1, 0, 0, 0]);
+    }
+}
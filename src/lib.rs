@@ -25,36 +25,225 @@
 //! ### Initialization
 //! A new instance of the device is created as follows:
 //!
-//! ```rust
+//! ```ignore
 //! use akafugu_twidisplay::*:
 //!
 //! let mut akafugu = TWIDisplay::new(i2c, DEFAULT_ADDRESS);
 //! ```
 //!
+//! The startup settings can be applied in one call:
+//!
+//! ```ignore
+//! akafugu.init(Config { brightness: 127, mode: Mode::Scroll, clear: true }).await.unwrap();
+//! // or with full brightness, `Rotate` mode and a cleared display
+//! akafugu.init(Config::default()).await.unwrap();
+//! ```
+//!
+//! `clear_display()` leaves the dots, the mode and the brightness as they are. To get back
+//! to a known clean state, e.g. after a demo, the display can be reset: cleared, with all the dots
+//! and the colon off, in `Rotate` mode and at full brightness, as with `Config::default()`:
+//!
+//! ```ignore
+//! akafugu.reset().await.unwrap();
+//! ```
+//!
+//! To find out about a wrong address or wiring right away, the driver can also be created
+//! with `try_new()`, which checks that the display responds. If it doesn't, the I2C bus
+//! is given back with the error:
+//!
+//! ```ignore
+//! let mut akafugu = match TWIDisplay::try_new(i2c, DEFAULT_ADDRESS).await {
+//!     Ok(akafugu) => akafugu,
+//!     Err((_error, i2c)) => TWIDisplay::try_new(i2c, other_address).await.unwrap(),
+//! };
+//! ```
+//!
 //! The default address is 0x12. If the address was changed with the `set_address()` function,
 //! the new address must be used after a power down-power up sequence.  
 //!
+//! ### Sharing the bus
+//!
+//! The driver takes ownership of the I2C instance, but it can also work with a mutable reference
+//! through `TWIDisplayRef`, e.g. to talk to other devices on the same bus in between:
+//!
+//! ```ignore
+//! let mut akafugu = TWIDisplayRef::new(&mut i2c, DEFAULT_ADDRESS);
+//! akafugu.display_number(1234).await.unwrap();
+//!
+//! // the bus is free again once the driver is dropped
+//! let reading = sensor_read(&mut i2c).await;
+//! ```
+//!
+//! The bus can also be borrowed from the driver for a while, e.g. to reconfigure the peripheral,
+//! without giving up the state of the driver as `destroy()` does:
+//!
+//! ```ignore
+//! akafugu.i2c_mut().set_frequency(100_000);
+//! ```
+//!
+//! To share the bus between devices used at the same time, e.g. from different tasks,
+//! wrap it in a mutex-based device such as `I2cDevice` from `embassy-embedded-hal`
+//! and pass that to `TWIDisplay::new()`.
+//! The devices from `embedded-hal-bus` implement only the blocking I2C traits,
+//! they can be used with `TWIDisplayBlocking` instead (see `examples/shared_bus.rs`).
+//!
+//! ```ignore
+//! static I2C_BUS: StaticCell<Mutex<NoopRawMutex, I2c<'static, Async>>> = StaticCell::new();
+//! let i2c_bus = I2C_BUS.init(Mutex::new(i2c));
+//!
+//! let mut akafugu = TWIDisplay::new(I2cDevice::new(i2c_bus), DEFAULT_ADDRESS);
+//! let mut sensor = Sensor::new(I2cDevice::new(i2c_bus));
+//! ```
+//!
+//! ### Errors
+//!
+//! The error type implements `core::fmt::Display`, with a human-readable message for each error.
+//! With the `std` feature enabled, it also implements `std::error::Error`, so that it can be used
+//! with `?` in functions returning e.g. `Box<dyn std::error::Error>` on the host:
+//!
+//! ```ignore
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     // ...
+//!     block_on(akafugu.display_number(1234))?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! ### Blocking version
+//!
+//! With the `blocking` feature enabled, `TWIDisplayBlocking` provides the same functions
+//! for HALs implementing only the blocking `embedded-hal` I2C and delay traits, minus `.await`:
+//!
+//! ```ignore
+//! let mut akafugu = TWIDisplayBlocking::new(i2c, DEFAULT_ADDRESS);
+//! akafugu.display_number(1234).unwrap();
+//! ```
+//!
+//! `IdleDimmer` and `DisplayWriter` take the blocking driver with `tick_blocking()`,
+//! `activity_blocking()` and `flush_blocking()`.
+//!
+//! A blocking bus can be shared with other devices through `embedded-hal-bus`,
+//! e.g. `RefCellDevice` in a single thread, or `CriticalSectionDevice` and `AtomicDevice`
+//! for buses used from several tasks or interrupts:
+//!
+//! ```ignore
+//! let bus = RefCell::new(i2c);
+//! let mut akafugu = TWIDisplayBlocking::new(RefCellDevice::new(&bus), DEFAULT_ADDRESS);
+//! let mut sensor = Sensor::new(RefCellDevice::new(&bus));
+//! ```
+//!
+//! ### Logging with defmt
+//!
+//! With the `defmt` feature enabled, the error type (for bus errors implementing `defmt::Format`)
+//! and the `Mode`, `TempUnits` and `DateFormat` types implement `defmt::Format` and can be logged directly:
+//!
+//! ```ignore
+//! if let Err(e) = akafugu.display_number(1234).await {
+//!     defmt::error!("display error: {}", e);
+//! }
+//! ```
+//!
 //!
 //! ### Main functions
 //!
 //! Display can be cleared with the following command:
-//! ```rust
+//! ```ignore
 //! akafugu.clear_display().await.unwrap();
 //! ```
 //!
+//! The clear command returns immediately, and a character sent right after it may occasionally
+//! get lost. If the next command must not race the clear, use the confirmed variant,
+//! which waits `CLEAR_SETTLE_MS` milliseconds before returning:
+//! ```ignore
+//! akafugu.clear_display_confirmed(&mut delay).await.unwrap();
+//! ```
+//!
 //! Digits and/or characters can either be simply sent to display, or displayed at defined positions.
 //!
-//! ```rust
+//! ```ignore
 //! // display digit '7' at position 2 (positions are 0,1,2,3 from left to right)
 //! akafugu.display_digit(2, 7).await.unwrap();
 //! // display character 'P' at position 3
 //! akafugu.display_char(3,'P').await.unwrap();
+//! // blank the digit at position 2 again, e.g. to blink it
+//! akafugu.clear_digit(2).await.unwrap();
+//! ```
+//!
+//! A value between 0 and 99 can be written on a pair of positions, leaving the other ones as they are,
+//! e.g. for clocks updating the hours and the minutes separately:
+//! ```ignore
+//! akafugu.display_two_digits(0, hours).await.unwrap();
+//! akafugu.display_two_digits(2, 7).await.unwrap(); // shows `07` on positions 2 and 3
+//! ```
+//!
+//! Four separate digits, e.g. from a keypad entry, can be written at once:
+//! ```ignore
+//! akafugu.display_digits([1, 2, 3, 4]).await.unwrap();
+//! assert!(matches!(akafugu.display_digits([1, 2, 3, 10]).await, Err(Error::DigitOutOfRange(10))));
+//! ```
+//!
+//! and so can four separate characters:
+//! ```ignore
+//! akafugu.display_chars(['P', 'L', 'A', 'Y']).await.unwrap();
+//! assert!(matches!(akafugu.display_chars(['P', 'L', 'A', '~']).await, Err(Error::UnsupportedChar('~'))));
+//! ```
+//!
+//! Out-of-range arguments are reported with specific errors carrying the wrong value:
+//! `PositionOutOfRange`, `DigitOutOfRange`, `NumberTooLarge` for numbers that don't fit,
+//! `AddressOutOfRange`, `DateOutOfRange`, and `ValueOutOfRange` for the other arguments,
+//! e.g. hours or percentages. `InvalidInputData` is left for input that is wrong in other ways.
+//! ```ignore
+//! assert!(matches!(akafugu.display_char(4, 'P').await, Err(Error::PositionOutOfRange(4))));
+//! assert!(matches!(akafugu.display_time(24, 0, true).await, Err(Error::ValueOutOfRange(24))));
+//! ```
+//!
+//! Positions can also be given as `Position`, whose constants can't be out of range.
+//! Positions computed at runtime can be checked once with `Position::new()`:
+//! ```ignore
+//! akafugu.display_char(Position::P3, 'P').await.unwrap();
+//! ```
+//! ```
+//! # use akafugu_twidisplay_async::*;
+//! assert_eq!(Position::new(3), Some(Position::P3));
+//! assert_eq!(Position::new(4), None);
+//! ```
+//!
+//! In the same way, characters can be checked once with `DisplayChar::new()`, which only accepts
+//! characters that can be displayed. `display_char()` also takes raw segment masks:
+//! ```
+//! # use akafugu_twidisplay_async::*;
+//! assert_eq!(DisplayChar::new('C').unwrap().as_char(), Some('C'));
+//! assert_eq!(DisplayChar::new('€'), None);
+//! ```
+//! ```ignore
+//! let unit = DisplayChar::new('C').unwrap();
+//! akafugu.display_char(3, unit).await.unwrap();
+//! akafugu.display_char(0, SegmentMask::SEG_G).await.unwrap(); // a dash drawn from segments
+//! ```
+//!
+//! Hexadecimal digits (0x0 - 0xF) can be displayed at defined positions as well:
+//! ```ignore
+//! // display 'A' at position 2
+//! akafugu.display_hex_digit(2, 0x0A).await.unwrap();
+//! ```
+//!
+//! or sent without a position, e.g. to scroll through a hex dump:
+//! ```ignore
+//! for byte in data {
+//!     akafugu.send_hex_digit(byte >> 4).await.unwrap();
+//!     akafugu.send_hex_digit(byte & 0x0F).await.unwrap();
+//! }
+//! ```
+//!
+//! A 16-bit value can be displayed as four hexadecimal digits, e.g. to inspect register values:
+//! ```ignore
+//! akafugu.display_hex(0xBEEF).await.unwrap();
 //! ```
 //!
 //! If a digit/character is just sent to the display, it will appear according to the selected mode
 //! (scroll or rotate) - please see the documentation.
 //!
-//! ```rust
+//! ```ignore
 //! akafugu.send_char('A').await.unwrap();
 //! akafugu.send_char('B').await.unwrap();
 //! akafugu.send_char('C').await.unwrap();
@@ -63,7 +252,7 @@
 //!
 //! This will display `ABCD`.
 //!
-//! ```rust
+//! ```ignore
 //! akafugu.send_char('E').await.unwrap();
 //! ```
 //!
@@ -71,40 +260,329 @@
 //! * in SCROLL mode: 'BCDE'
 //! * in ROTATE mode: 'EBCD'
 //!
+//! Only printable ASCII characters from the space up to `y` can be displayed, other characters
+//! are rejected with `Error::UnsupportedChar`. This can be checked beforehand:
+//!
+//! ```
+//! # use akafugu_twidisplay_async::*;
+//! assert!(is_displayable('A'));
+//! assert!(is_displayable('a')); // lowercase letters up to 'y' are sent as they are
+//! assert!(!is_displayable('€'));
+//! ```
+//!
+//! Not all of them are legible on 7 segments, though. The legible ones are listed with their
+//! segment masks in `CHAR_SEGMENTS`:
+//!
+//! ```
+//! # use akafugu_twidisplay_async::*;
+//! assert!(char_is_supported('H'));
+//! assert!(!char_is_supported('M'));
+//! for ch in supported_chars() {
+//!     assert!(is_displayable(ch));
+//! }
+//! ```
+//!
 //! Text can be sent to display as string literals:
 //!
-//! ```rust
+//! ```ignore
 //! akafugu.send_text("HELLO LOOP PULL CALL").await.unwrap();
 //! ```
 //!
+//! Many characters are only legible in uppercase, lowercase text can be sent uppercased:
+//!
+//! ```ignore
+//! akafugu.send_text_upper("hello world").await.unwrap(); // sends `HELLO WORLD`
+//! ```
+//!
+//! Arbitrary text, e.g. received over a serial port, can be sent with the characters
+//! that aren't legible on 7 segments uppercased, where that makes them legible, or replaced with spaces:
+//!
+//! ```ignore
+//! akafugu.send_text_sanitized("Hi!€").await.unwrap(); // sends `HI  `
+//! ```
+//!
+//! Longer text can also be scrolled across the display at a given pace, independent of the mode:
+//!
+//! ```ignore
+//! // one step every 300 ms
+//! akafugu.scroll_text("HELLO LOOP PULL CALL", &mut delay, 300).await.unwrap();
+//! ```
+//!
+//! A full frame (characters and dots) can be composed in memory with `DisplayBuffer`
+//! and then written at once, as one transaction for the characters and one for the dots:
+//! ```ignore
+//! // show `12.34`
+//! let mut buf = DisplayBuffer::new();
+//! buf.chars = [1, 2, 3, 4];
+//! buf.dots[1] = true;
+//! akafugu.flush(&buf).await.unwrap();
+//! ```
+//!
+//! Numbers and text can be shown together with the dots the same way, without a buffer:
+//! ```ignore
+//! akafugu.display_number_with_dots(1234, [false, true, false, false]).await.unwrap(); // shows `12.34`
+//! akafugu.display_text_with_dots("ABC", [false, false, true, false]).await.unwrap(); // shows `ABC. `
+//! ```
+//!
+//! The display can also be written to with `write!()`, through `DisplayWriter`. The characters are
+//! collected in a buffer, and written to the display left-aligned with `flush()`:
+//! ```ignore
+//! use core::fmt::Write;
+//!
+//! let mut writer = DisplayWriter::new();
+//! write!(writer, "{:>4}", 42).unwrap();
+//! writer.flush(&mut akafugu).await.unwrap(); // shows `  42`
+//! ```
+//!
+//! A fixed four-character code can be shown in a single I2C transaction. If any of the characters
+//! can't be displayed, `Error::UnsupportedChar` is returned and nothing is written:
+//! ```ignore
+//! akafugu.display_code(['E', '0', '4', '2']).await.unwrap();
+//! ```
+//!
+//! Several position and dot updates can also be collected and written at once,
+//! e.g. for animations. Between `begin_frame()` and `commit_frame()`, the positions and the dots
+//! are only kept by the driver, and `commit_frame()` writes them in one transaction
+//! (plus one for the dots, if they were changed):
+//! ```ignore
+//! akafugu.begin_frame();
+//! for (position, digit) in [1, 2, 3, 4].into_iter().enumerate() {
+//!     akafugu.display_digit(position as u8, digit).await.unwrap(); // nothing is sent yet
+//! }
+//! akafugu.commit_frame().await.unwrap(); // shows `1234`
+//! ```
+//!
+//! For fast refresh loops, the whole display can be replaced at once, without the flash
+//! caused by clearing it first. The text is padded with spaces or truncated to four characters:
+//! ```ignore
+//! akafugu.redraw_text("RUN").await.unwrap(); // shows `RUN `
+//! ```
+//!
+//! Unlike `send_text()`, which depends on the display mode, text can also be placed at positions 0-3
+//! explicitly. Short text is padded with spaces, longer text is truncated:
+//! ```ignore
+//! akafugu.display_text_fixed("AB").await.unwrap(); // shows `AB  `
+//! akafugu.display_text_fixed("ABCDE").await.unwrap(); // shows `ABCD`
+//! ```
+//!
+//! `display_static_text()` does the same on all the positions of the display, also on 8-digit units,
+//! so the text is placed the same way whatever mode was set:
+//! ```ignore
+//! akafugu.set_mode(Mode::Scroll).await.unwrap();
+//! akafugu.display_static_text("STOP").await.unwrap(); // shows `STOP`, not scrolled
+//! ```
+//!
 //! Numbers from 0-9999 range can be displayed with the following function:
-//! ```rust
+//! ```ignore
 //! akafugu.display_number(1234).await.unwrap();
 //! ```
 //! _NOTE_: Numbers will be displayed with leading zeroes, e.g. `0023`.
+//! To show them without, use the padded variant:
+//! ```ignore
+//! akafugu.display_number_padded(23, false).await.unwrap(); // shows `  23`
+//! ```
+//!
+//! The firmware can also render a number by itself, with a single 3-byte command instead of
+//! the four positions. _NOTE_: this is not verified on hardware yet; the byte order and
+//! the rendering (e.g. leading zeros, which `display_number()` always shows) are up to the firmware.
+//! Since the driver doesn't know the resulting content, `redraw()` skips the positions afterwards:
+//! ```ignore
+//! akafugu.display_word(1234).await.unwrap();
+//! ```
+//!
+//! Signed numbers from -999 to 9999 are shown right-aligned with no leading zeros,
+//! with the minus sign at the first position:
+//! ```ignore
+//! akafugu.display_signed(-7).await.unwrap(); // shows `-  7`
+//! akafugu.display_signed(-999).await.unwrap(); // shows `-999`
+//! // -1000 would need five positions
+//! assert!(akafugu.display_signed(-1000).await.is_err());
+//! ```
+//!
+//! Numbers can also be aligned left, right or centered, without leading zeros:
+//! ```ignore
+//! akafugu.display_number_aligned(42, Alignment::Center).await.unwrap(); // shows ` 42 `
+//! ```
+//!
+//! On always-on dashboards it may be preferable to never fail on the magnitude of the value.
+//! Numbers from -999 to 9999 are then shown right-aligned with no leading zeros, anything else
+//! as the chosen glyph on all four positions:
+//! ```ignore
+//! akafugu.display_clamped(-42, '-').await.unwrap(); // shows `- 42`
+//! akafugu.display_clamped(12345, '-').await.unwrap(); // shows `----`
+//! ```
+//!
+//! Alternatively, numbers that don't fit can saturate: the highest number the display can show
+//! is displayed instead, with the rightmost dot (`DOT_RIGHT`) lit as overflow indicator.
+//! The dot is turned off again for numbers that fit:
+//! ```ignore
+//! akafugu.display_number_saturating(9999).await.unwrap(); // shows `9999`
+//! akafugu.display_number_saturating(12345).await.unwrap(); // shows `9999.`
+//! akafugu.display_number_saturating(0).await.unwrap(); // shows `0000`
+//! ```
+//!
+//! Decimal numbers, e.g. sensor readings, are shown left-aligned with the dot after the integer part:
+//! ```ignore
+//! akafugu.display_float(23.4, 1).await.unwrap(); // shows `23.4`
+//! akafugu.display_float(-0.5, 1).await.unwrap(); // shows `-0.5`
+//! // 1000.0 would need five digits
+//! assert!(akafugu.display_float(1000.0, 1).await.is_err());
+//! ```
+//!
+//! On MCUs without an FPU, the same can be done with fixed-point values, choosing the dot position:
+//! ```ignore
+//! akafugu.display_fixed(23, 4, 1).await.unwrap(); // shows `23.4`
+//! akafugu.display_fixed(23, 4, 2).await.unwrap(); // shows ` 23.4`
+//! ```
+//!
+//! Raw segment masks can be written to all four positions in a single I2C transaction.
+//! Bit 0 is segment A (top), bits 1-5 are segments B-F going clockwise, bit 6 is segment G (middle)
+//! and bit 7 is the decimal point:
+//! ```ignore
+//! // display `-  -` using only the middle segments of the outer positions
+//! akafugu.write_frame(&[0b0100_0000, 0, 0, 0b0100_0000]).await.unwrap();
+//! ```
+//!
+//! Simple animations can be played from a slice of such frames, each shown for `frame_ms` milliseconds.
+//! With `repeat` set to `true` the animation loops until an error occurs, e.g. in a dedicated task:
+//! ```ignore
+//! let frames = [[0b0100_0000, 0, 0, 0], [0, 0b0100_0000, 0, 0], [0, 0, 0b0100_0000, 0], [0, 0, 0, 0b0100_0000]];
+//! akafugu.play_frames(&frames, 150, false, &mut delay).await.unwrap();
+//! ```
+//!
+//! Custom characters, such as a degree symbol, can be built with the `SegmentMask` constants
+//! and shown at a selected position:
+//! ```ignore
+//! let degree = SegmentMask::SEG_A | SegmentMask::SEG_B | SegmentMask::SEG_F | SegmentMask::SEG_G;
+//! akafugu.define_custom_char(3, degree).await.unwrap();
+//! ```
+//!
+//! Some board variants show raw segments inverted (lit segments dark and vice versa).
+//! This can be worked around by enabling the inversion of segments A-G, both in raw segment writes
+//! and for the characters in `CHAR_SEGMENTS`, which are then written as inverted segment masks:
+//! ```ignore
+//! akafugu.set_invert(true);
+//! ```
+//!
+//! For displays mounted upside down, the positions can be reversed, so that position 0
+//! is the rightmost one, and the segments rotated. The digits and the other characters
+//! in `CHAR_SEGMENTS` are then written as rotated segment masks, so they read correctly:
+//! ```ignore
+//! akafugu.set_orientation(true);
+//! akafugu.display_char(0, 'A').await.unwrap(); // written at position 3, rotated
+//! ```
+//! ```
+//! # use akafugu_twidisplay_async::*;
+//! assert_eq!(segment_flip(SegmentMask::SEG_A), SegmentMask::SEG_D);
+//! assert_eq!(segment_flip(0x7d), 0x6f); // '6' becomes '9'
+//! ```
+//!
+//! Without it, only a few digits can be read upside down: 0, 1 (on the other side
+//! of the position), 2, 5 and 8 look the same, 6 and 9 swap, the others are not legible.
 //!
 //! Dots can be turned on or off using this function:
-//! ```rust
+//! ```ignore
 //! // this will turn on the first and the third dot from the left
 //! akafugu.display_dots([true, false, true, false]).await.unwrap();
 //! ```
 //!
+//! The dots are indexed 0-3 from the left, like the positions, and can be referred to by name:
+//! `DOT_LEFT`, `DOT_CENTER_LEFT` (the separator in HH.MM and MM.SS), `DOT_CENTER_RIGHT` and `DOT_RIGHT`.
+//! ```ignore
+//! let mut dots = [false; 4];
+//! dots[DOT_RIGHT as usize] = true;
+//! akafugu.display_dots(dots).await.unwrap(); // dots register 0b0001_0000
+//! ```
+//!
+//!
+//! A single dot can be turned on or off, leaving the other dots as they are:
+//! ```ignore
+//! akafugu.set_dot(DOT_LEFT, true).await.unwrap();
+//! akafugu.set_dot(DOT_CENTER_RIGHT, true).await.unwrap(); // both dots are on now
+//! ```
+//!
+//! On clock displays with a center colon, the colon can be switched separately from the dots:
+//! ```ignore
+//! akafugu.set_colon(seconds % 2 == 0).await.unwrap();
+//! ```
+//!
+//! The driver keeps track of what it has written to the display, which can be written again
+//! with `redraw()`. On a flaky bus, operations issuing several I2C transactions (e.g. `display_time()`)
+//! can roll back to the previous content when one of the transactions fails, instead of leaving
+//! a half-updated display. This costs up to two additional transactions on failure:
+//! ```ignore
+//! akafugu.set_rollback_on_error(true);
+//! ```
+//!
+//! `restore()` also writes the last brightness set, e.g. after the display was power cycled:
+//! ```ignore
+//! akafugu.restore().await.unwrap();
+//! ```
 //!
 //! ### Control functions
 //!
 //! Display mode can be changed as follows:
 //!
-//! ```rust
+//! ```ignore
 //! akafugu.set_mode(Mode::Scroll).await.unwrap(); // default mode is `Rotate`
 //! ```
 //!
+//! The driver keeps the last mode set, e.g. to check how `send_char()` will behave:
+//! ```ignore
+//! assert_eq!(akafugu.mode(), Mode::Scroll);
+//! ```
+//!
 //! Brightness can be set between 0 and 255, where 127 is approx. 50% brightness.
-//! ```rust
+//! ```ignore
 //! akafugu.set_brightness(200).await.unwrap();
 //! ```
 //!
+//! Some units turn the display off completely at very low values. `MIN_VISIBLE_BRIGHTNESS`
+//! is a rough, panel-dependent estimate of the lowest brightness that keeps it lit,
+//! worth checking on the actual unit.
+//!
+//! Brightness can also be set as a percentage:
+//! ```ignore
+//! akafugu.set_brightness_percent(50).await.unwrap(); // same as set_brightness(127)
+//! ```
+//!
+//! The last brightness set is remembered by the driver and can be read back with `brightness()`.
+//!
+//! For a short attention cue, the brightness can be raised for a while, then restored
+//! to the last brightness set, keeping the content on the display:
+//! ```ignore
+//! akafugu.brightness_flash(255, 200, &mut delay).await.unwrap();
+//! ```
+//!
+//! The whole display can be flashed, e.g. three times with 300 ms on and 200 ms off:
+//! ```ignore
+//! akafugu.blink(3, 300, 200, &mut delay).await.unwrap();
+//! ```
+//!
+//! The brightness can also be faded gradually, e.g. from off to full within 10 seconds:
+//! ```ignore
+//! akafugu.fade_brightness(0, 255, 100, 100, &mut delay).await.unwrap();
+//! ```
+//!
+//! The display can be dimmed automatically after a period of inactivity with `IdleDimmer`.
+//! The application reports the elapsed time and content updates, and the dimmer
+//! restores the last brightness set on the next update:
+//! ```ignore
+//! // dim to 20 after 30 seconds without updates
+//! let mut dimmer = IdleDimmer::new(30_000, 20);
+//!
+//! loop {
+//!     if let Some(reading) = new_reading() {
+//!         akafugu.display_number(reading).await.unwrap();
+//!         dimmer.activity(&mut akafugu).await.unwrap();
+//!     }
+//!     Timer::after_millis(100).await;
+//!     dimmer.tick(&mut akafugu, 100).await.unwrap();
+//! }
+//! ```
+//!
 //! The I2C address of the device can be changed from the default 0x12 as follows:
-//! ```rust
+//! ```ignore
 //! akafugu.set_address(0x20).await.unwrap();
 //! ```
 //!
@@ -114,13 +592,111 @@
 //! but addresses including and over 0x40 don't seem to work correctly, even though
 //! they are correctly displayed. In such case 0x00 must be used to access the device and change the address again.
 //! For this reason in this driver the address setting is restricted to 0x00-0x39 range.
+//! Other addresses are rejected with `Error::AddressOutOfRange`.
+//! To experiment with the full range anyway, the address can be set without any checks:
+//! ```ignore
+//! akafugu.set_address_unchecked(0x69).await.unwrap();
+//! ```
+//!
+//! Neither function changes the address the driver talks to, since the new address is only active
+//! after a power cycle.
+//!
+//! A display set to an address that doesn't work can still be reached on address 0x00,
+//! and set back to the default address:
+//! ```ignore
+//! let mut akafugu = TWIDisplay::new(i2c, 0x00);
+//! akafugu.reset_to_default_address().await.unwrap();
+//! ```
+//!
+//! For displays applying the new address immediately, the driver can switch to it as well.
+//! The address the driver talks to can be read back with `address()`:
+//! ```ignore
+//! akafugu.change_address_now(0x20).await.unwrap();
+//! assert_eq!(akafugu.address(), 0x20);
+//! ```
 //!
 //! To show the current I2C address use the following command:
-//! ```rust
+//! ```ignore
 //! akafugu.display_address().await.unwrap();
 //! ```
 //! The same can be achieved by simply connecting only the VCC and GND pins of the display.
 //!
+//! ### Raw commands
+//!
+//! As an escape hatch for experimenting with commands the driver doesn't wrap yet,
+//! e.g. the undocumented registers 0x87 and 0x88, raw bytes can be sent as they are
+//! in a single I2C write. The driver doesn't know what they do, so `redraw()` and `restore()`
+//! will only cover the dots and brightness afterwards, until the positions are written again:
+//! ```ignore
+//! akafugu.send_raw(&[0x88, 0x12, 0x34]).await.unwrap();
+//! ```
+//!
+//! ### Demo
+//!
+//! To see the main functions of the driver in action, e.g. to check a new board, run the demo:
+//! ```ignore
+//! akafugu.demo(&mut delay).await.unwrap();
+//! ```
+//!
+//! For bring-up or production checks, the self-test lights all the segments on all the positions,
+//! then all the dots, and sweeps the brightness, so dead segments or wiring faults can be spotted:
+//! ```ignore
+//! akafugu.self_test(&mut delay).await.unwrap();
+//! ```
+//!
+//! ### Diagnostic functions
+//!
+//! The firmware revision of the display can be read, e.g. to handle differences between batches:
+//! ```ignore
+//! let revision = akafugu.get_firmware_rev().await.unwrap();
+//! ```
+//!
+//! The number of digits can be read as well, e.g. to tell apart 4- and 8-digit units.
+//! This reads from the device on every call:
+//! ```ignore
+//! let digits = akafugu.get_number_digits().await.unwrap();
+//! ```
+//!
+//! For displays that can be unplugged, a supervisory task can check whether the display
+//! still responds and set it up again once it's back:
+//! ```ignore
+//! let mut present = true;
+//! loop {
+//!     let was_present = present;
+//!     present = akafugu.is_present().await;
+//!     if present && !was_present {
+//!         akafugu.init(Config::default()).await.unwrap();
+//!         akafugu.restore().await.unwrap();
+//!     }
+//!     Timer::after_millis(500).await;
+//! }
+//! ```
+//!
+//! ### 8-digit units
+//!
+//! The driver assumes a 4-digit unit by default. For the 8-digit variant, the number of digits
+//! can be given at construction, or read from the device with `detect_digits()`.
+//! It sets the range of the positions accepted by e.g. `display_char()`,
+//! and the numbers shown by `display_number()` and `display_long_number()`:
+//! ```ignore
+//! let mut akafugu = TWIDisplay::new_with_digits(i2c, DEFAULT_ADDRESS, 8);
+//! // or: akafugu.detect_digits().await.unwrap();
+//! akafugu.display_long_number(12345678).await.unwrap(); // shows `12345678`
+//! akafugu.display_char(7, 'E').await.unwrap();
+//! ```
+//! The other functions, e.g. for time and temperature, use the first four positions.
+//! The dots are limited to the four dots at positions 0-3.
+//!
+//! ### Bus load
+//!
+//! On a shared bus it can be useful to know how many I2C transactions an operation
+//! will issue before calling it:
+//! ```
+//! # use akafugu_twidisplay_async::*;
+//! let count = transaction_count(Operation::DisplayTime); // number and dots
+//! assert_eq!(count, 2);
+//! ```
+//!
 //! ### Convenience functions
 //! The driver has three additional functions, that can be useful for clock or sensor applications.
 //!
@@ -128,7 +704,7 @@
 //!
 //! Time is displayed in HH.MM format, with the central dot displayed or not:
 //!
-//! ```rust
+//! ```ignore
 //!
 //! // get time from the clock
 //! let (hours, minutes, seconds) = some_rtc_function();
@@ -141,12 +717,87 @@
 //! }
 //! ```
 //!
+//! The firmware also has a native command showing the time in a single 3-byte write.
+//! _NOTE_: it is not verified on hardware yet and may not work on every firmware revision,
+//! so `display_time()`, which uses the regular position commands, remains the portable choice.
+//! Check the result on the display (and the firmware revision with `get_firmware_rev()`)
+//! before switching to it:
+//!
+//! ```ignore
+//! akafugu.display_time_native(hours, minutes).await.unwrap()
+//! ```
+//!
+//! Time can also be shown in 12-hour format, without the leading zero. Since there is no room
+//! for an AM/PM indicator, the rightmost dot is on for PM:
+//!
+//! ```ignore
+//! // 13:05 is shown as ` 1.05` with the rightmost dot on
+//! akafugu.display_time_12h(13, 5, true).await.unwrap()
+//! ```
+//!
+//! The same can be done directly with the `(hours, minutes, seconds)` tuple returned by the clock:
+//!
+//! ```ignore
+//! akafugu.display_clock_tuple(some_rtc_function(), true).await.unwrap()
+//! ```
+//!
+//! The leading zero of the hours can be left out, so that e.g. 9:05 is shown as ` 9.05`:
+//!
+//! ```ignore
+//! akafugu.display_time_padded(hours, minutes, true, false).await.unwrap()
+//! ```
+//!
+//! All four digits are rewritten on every update, so rolling over e.g. from `12.59` to `13.00`
+//! or from `05.59` to `06.00` in MM.SS mode always updates both the tens and the units.
+//!
+//! Since four digits can't show hours, minutes and seconds at once, the clock can be switched
+//! between HH.MM and MM.SS, e.g. to show the seconds while a button is pressed:
+//!
+//! ```ignore
+//! let (hours, minutes, seconds) = some_rtc_function();
+//! let show_seconds = button.is_low();
+//!
+//! akafugu.display_clock_toggle(hours, minutes, seconds, show_seconds).await.unwrap()
+//! ```
+//!
+//! Elapsed time, e.g. of a stopwatch, is shown in MM.SS format, with minutes up to 99:
+//!
+//! ```ignore
+//! akafugu.display_stopwatch(elapsed / 60, elapsed % 60, true).await.unwrap()
+//! ```
+//!
+//! A countdown given in seconds is shown in MM.SS format under an hour, and in HH.MM format above:
+//!
+//! ```ignore
+//! akafugu.display_countdown(90, true).await.unwrap(); // shows `01.30`
+//! akafugu.display_countdown(3661, true).await.unwrap(); // shows ` 1.01`
+//! ```
+//!
+//! An elapsed duration can be shown in the best fitting format, chosen automatically:
+//! seconds followed by `S` under a minute, MM.SS under an hour, HH.MM (without the leading zero)
+//! under a day, and days followed by `d` up to 999 days. Longer durations are rejected:
+//!
+//! ```ignore
+//! akafugu.display_duration(45).await.unwrap(); // shows ` 45S`
+//! akafugu.display_duration(300).await.unwrap(); // shows `05.00`
+//! akafugu.display_duration(7200).await.unwrap(); // shows ` 2.00`
+//! akafugu.display_duration(200_000).await.unwrap(); // shows `  2d`
+//! ```
+//!
+//! A subtle seconds cue can be added to an HH.MM clock by blinking the rightmost dot,
+//! which is on for even seconds, without touching the digits or the central dot:
+//!
+//! ```ignore
+//! akafugu.display_time(hours, minutes, true).await.unwrap();
+//! akafugu.seconds_pip(seconds).await.unwrap();
+//! ```
+//!
 //! #### Display date
 //!  
 //!
 //! Date can be displayed either in MMDD or DDMM format, with the central dot on or off.
 //!
-//! ```rust
+//! ```ignore
 //!
 //! // get date from the clock
 //! let (month, day) = some_rtc_function();
@@ -156,6 +807,20 @@
 //!
 //! ```
 //!
+//! `display_date()` accepts February 29 in any year. When the year is known,
+//! `display_date_checked()` also rejects February 29 in non-leap years:
+//!
+//! ```ignore
+//! akafugu.display_date_checked(2024, 2, 29, DateFormat::DDMM, true).await.unwrap(); // shows `29.02`
+//! assert!(akafugu.display_date_checked(2023, 2, 29, DateFormat::DDMM, true).await.is_err());
+//! ```
+//!
+//! The year is shown on all four digits, with the dots off:
+//!
+//! ```ignore
+//! akafugu.display_year(2024).await.unwrap(); // shows `2024`
+//! ```
+//!
 //!
 //! #### Display temperature
 //!
@@ -166,8 +831,11 @@
 //! reliable readings, e.g. -30 and +60 Celsius degrees, etc.
 //! Thresholds are optional and if not given, will default to the minimum and maximum limits, which are set to -99 and 999, respectively.
 //! If the supplied value exceeds the limit, the display will show `----`.  
+//! Values are right-aligned, with the unit at the last position: 5 is shown as `  5C`, 25 as ` 25C`
+//! and 100 as `100C`. Negative values have the minus sign right before the first digit,
+//! e.g. -5 is shown as ` -5C`, -50 as `-50C` and -99 as `-99C`.
 //!
-//! ```rust
+//! ```ignore
 //! let temp_reading = some_sensor_reading();
 //! // display temperature with unit 'C', lower threshold at -50 degrees,
 //! // no upper threshold (defaults to +999)
@@ -175,66 +843,423 @@
 //! akafugu.display_temperature(temperature, TempUnits::Celsius, Some(-50), None).await.unwrap();
 //! ```
 //!
-//! #### Display humidity
+//! A temperature measured in Celsius degrees can be converted to Fahrenheit by the driver.
+//! The thresholds are then given in Fahrenheit degrees:
 //!
-//! Displays integer humidity values with a default unit 'H', no leading zeros.
-//! The function allows setting lower and upper threshold: if the supplied value is below the lower threshold,
-//! the display will show `-LL-`, if above the upper threshold, it will show `-HH-`.
-//! This can be useful for sensor applications such as weather stations: thresholds can be set to the limits of
-//! reliable readings, e.g. between 10 and 90% of relative humidity.
-//! Thresholds are optional and if not given, will default to the minimum and maximum limits, which are set to 0 and 100, respectively.
-//! If the supplied value exceeds the limit, the display will show `----`.  
+//! ```ignore
+//! // shows `212F`
+//! akafugu.display_temperature_converted(100, TempUnits::Fahrenheit, None, Some(500)).await.unwrap();
+//! ```
 //!
-//! ```rust
-//! let hum_reading = some_sensor_reading();
-//! // display humidity, lower threshold at 10%, upper threshold at 90%.
-//! // temp_reading < 10 will show as `-LL-`, temp_reading > 90 will show as `-HH-`,
-//! // readings below 0 or above 100 will show as `----`
-//! akafugu.display_humidity(humidity, Some(10), Some(90)).await.unwrap();
+//! A reading hovering around a threshold would make the display flicker between the value
+//! and `-HH-` or `-LL-`. With hysteresis, the value is only shown again once the reading
+//! is back within the thresholds by more than the hysteresis:
+//!
+//! ```ignore
+//! // shows `-HH-` above 50, then stays there down to 48, and shows ` 47C` again at 47
+//! akafugu.display_temperature_hyst(temperature, TempUnits::Celsius, None, Some(50), 2).await.unwrap();
 //! ```
-
-
-#![deny(unsafe_code)]
-#![deny(missing_docs)]
-#![no_std]
-
-use embedded_hal_async as hal;
-
-use hal::i2c::I2c;
-
-/// All possible errors in this crate
-#[derive(Debug)]
-pub enum Error<E> {
-    /// I2C bus error
-    I2C(E),
-    /// Invalid input data
-    InvalidInputData,
-}
-
-struct Register;
-
-// THESE WILL BE USED FOR VARIOUS OPERATIONS, E.G. SETTING POSITION
+//!
+//! Instead of the markers, a temperature beyond the thresholds can also be clamped to them,
+//! or not shown at all:
+//!
+//! ```ignore
+//! // shows ` 60C`
+//! akafugu.display_temperature_clamped(250, TempUnits::Celsius, None, Some(60), ClampBehavior::Clamp).await.unwrap();
+//! // shows nothing, with the dots off
+//! akafugu.display_temperature_clamped(250, TempUnits::Celsius, None, Some(60), ClampBehavior::Blank).await.unwrap();
+//! ```
+//!
+//! Weather stations can also show where the temperature is going, on the dots:
+//! the rightmost dot is lit when rising, the leftmost one when falling:
+//!
+//! ```ignore
+//! let trend = if temperature > last { Trend::Rising } else if temperature < last { Trend::Falling } else { Trend::Steady };
+//! akafugu.display_temperature_with_trend(temperature, TempUnits::Celsius, trend).await.unwrap(); // e.g. ` 21C.`
+//! ```
+//!
+//! Temperature with one decimal is given in tenths of a degree, between -9.9 and 99.9 degrees.
+//! The value takes the first three positions, with the dot after the second one,
+//! followed by the unit:
+//!
+//! ```ignore
+//! akafugu.display_temperature_decimal(234, TempUnits::Celsius).await.unwrap(); // shows `23.4C`
+//! akafugu.display_temperature_decimal(-5, TempUnits::Celsius).await.unwrap(); // shows `-0.5C`
+//! ```
+//!
+//! Since both the daily low and high temperature don't fit on the display, they can be shown
+//! one after the other, separated by ` -- `:
+//!
+//! ```ignore
+//! loop {
+//!     // each value is shown for 1 second
+//!     akafugu.scroll_range(-3, 12, TempUnits::Celsius, &mut delay, 1000).await.unwrap();
+//! }
+//! ```
+//!
+//! #### Display values with a unit
+//!
+//! Values between -99 and 999 can be shown with any unit character that can be displayed,
+//! e.g. 'A' for amps or 'U' for volts, with the same layout and thresholds as the temperature:
+//!
+//! ```ignore
+//! akafugu.display_value_with_unit(12, 'A', None, Some(20)).await.unwrap(); // shows ` 12A`
+//! akafugu.display_value_with_unit(25, 'A', None, Some(20)).await.unwrap(); // shows `-HH-`
+//! ```
+//!
+//! #### Display values with two-character units
+//!
+//! Values between 0 and 99 can be shown with a two-letter unit, e.g. for instrumentation.
+//! Values outside this range are rejected, as they would not fit in the two remaining positions.
+//! The unit can only use characters up to `y` (see `is_displayable()`), and is legible only with
+//! the characters in `CHAR_SEGMENTS`, e.g. `db`, `Pa` or `HP`. Units like `Hz` or `kW` can't be shown:
+//!
+//! ```ignore
+//! akafugu.display_value_unit2(42, ['d', 'b']).await.unwrap(); // shows `42db`
+//! assert!(matches!(akafugu.display_value_unit2(50, ['H', 'z']).await, Err(Error::UnsupportedChar('z'))));
+//! ```
+//!
+//! #### Display a percentage
+//!
+//! Shows a percentage between 0 and 100 right-aligned, followed by `P` since there is no `%` glyph:
+//!
+//! ```ignore
+//! akafugu.display_percentage(85).await.unwrap(); // shows ` 85P`
+//! ```
+//!
+//! #### Display an IP address octet
+//!
+//! Network addresses don't fit on the display, but they can be shown one octet at a time.
+//! The octet is right-aligned in positions 0-2, with the dot after it showing that
+//! more octets follow:
+//!
+//! ```ignore
+//! for octet in [192, 168, 1, 5] {
+//!     akafugu.display_octet(octet).await.unwrap(); // shows `192.`, `168.`, `  1.`, `  5.`
+//!     Timer::after_millis(1000).await;
+//! }
+//! ```
+//!
+//! #### Display battery level
+//!
+//! Shows `b` followed by a bar of three cells, each filled cell having the top, middle
+//! and bottom segments on: 0% shows the `b` only, 1-33% one cell, 34-66% two cells and 67-100% three cells.
+//!
+//! ```ignore
+//! akafugu.display_battery(50).await.unwrap(); // `b` and two cells
+//! ```
+//!
+//! #### Display a level bar
+//!
+//! Shows a level as a bar of bottom segments filled from the left, one position for every 25%:
+//! 0% shows an empty bar, 1-25% one position, 26-50% two positions and so on.
+//!
+//! ```ignore
+//! akafugu.display_bar(50).await.unwrap(); // `__  `
+//! ```
+//!
+//! #### Busy spinner
+//!
+//! A single segment going around the outside of a position, advanced by the application:
+//!
+//! ```ignore
+//! let mut frame = 0;
+//! while busy() {
+//!     akafugu.spinner_frame(3, frame).await.unwrap();
+//!     frame = (frame + 1) % 6;
+//!     Timer::after_millis(100).await;
+//! }
+//! ```
+//!
+//! #### Display humidity
+//!
+//! Displays integer humidity values with a default unit 'H', no leading zeros.
+//! The function allows setting lower and upper threshold: if the supplied value is below the lower threshold,
+//! the display will show `-LL-`, if above the upper threshold, it will show `-HH-`.
+//! This can be useful for sensor applications such as weather stations: thresholds can be set to the limits of
+//! reliable readings, e.g. between 10 and 90% of relative humidity.
+//! Thresholds are optional and if not given, will default to the minimum and maximum limits, which are set to 0 and 100, respectively.
+//! If the supplied value exceeds the limit, the display will show `----`.  
+//!
+//! ```ignore
+//! let hum_reading = some_sensor_reading();
+//! // display humidity, lower threshold at 10%, upper threshold at 90%.
+//! // temp_reading < 10 will show as `-LL-`, temp_reading > 90 will show as `-HH-`,
+//! // readings below 0 or above 100 will show as `----`
+//! akafugu.display_humidity(humidity, Some(10), Some(90)).await.unwrap();
+//! ```
+
+
+#![deny(unsafe_code)]
+#![deny(missing_docs)]
+#![no_std]
+
+#[cfg(feature = "std")]
+extern crate std;
+
+use embedded_hal_async as hal;
+
+use hal::delay::DelayNs;
+use hal::i2c::I2c;
+
+use core::fmt::Write;
+
+mod commands;
+
+#[cfg(feature = "blocking")]
+mod blocking;
+#[cfg(feature = "blocking")]
+pub use blocking::TWIDisplayBlocking;
+
+/// All possible errors in this crate
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error<E> {
+    /// I2C bus error
+    I2C(E),
+    /// Invalid input data that is not out of a range, e.g. a NaN or a low value above the high one
+    InvalidInputData,
+    /// Character that can't be shown on the display
+    UnsupportedChar(char),
+    /// Position beyond the last digit of the display (0-3, or 0-7 on 8-digit units)
+    PositionOutOfRange(u8),
+    /// Digit out of the 0-9 range (0x0-0xF for hexadecimal digits)
+    DigitOutOfRange(u8),
+    /// Number that doesn't fit on the display, e.g. above 9999 on 4-digit units or below -999
+    NumberTooLarge(i64),
+    /// I2C address out of the range accepted by the display (below 0x40)
+    AddressOutOfRange(u8),
+    /// Month or day that don't form a valid date, given as (month, day)
+    DateOutOfRange(u8, u8),
+    /// Other argument out of the range accepted by the function, e.g. a percentage above 100
+    /// or minutes above 59
+    ValueOutOfRange(u8),
+}
+
+impl<E: core::fmt::Debug> core::fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::I2C(e) => write!(f, "I2C bus error: {:?}", e),
+            Error::InvalidInputData => write!(f, "invalid input data"),
+            Error::UnsupportedChar(ch) => write!(f, "character {:?} can't be shown on the display", ch),
+            Error::PositionOutOfRange(position) => write!(f, "position {} out of range", position),
+            Error::DigitOutOfRange(digit) => write!(f, "digit {} out of range", digit),
+            Error::NumberTooLarge(number) => write!(f, "number {} doesn't fit on the display", number),
+            Error::AddressOutOfRange(address) => write!(f, "address {:#04x} out of range, must be below 0x40", address),
+            Error::DateOutOfRange(month, day) => write!(f, "month {} and day {} are not a valid date", month, day),
+            Error::ValueOutOfRange(value) => write!(f, "value {} out of range", value),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: core::fmt::Debug> std::error::Error for Error<E> {}
+
+struct Register;
+
+// THESE WILL BE USED FOR VARIOUS OPERATIONS, E.G. SETTING POSITION
 impl Register {
     const BRIGHTNESS_SETTING: u8 = 0x80;
     const I2C_ADDRESS_SETTING: u8 = 0x81;
     const CLEAR_DISPLAY: u8 = 0x82;
     const MODE_SETTING: u8 = 0x83;
-    const _CUSTOM_CHAR: u8 = 0x84; // not implemented yet
+    const CUSTOM_CHAR: u8 = 0x84; // followed by position and segment mask
     const DOTS: u8 = 0x85;
-    //const _DISPLAY_TIME          :u8 = 0x87; // not sure if this works
-    //const _DISPLAY_WORD          :u8 = 0x88;
+    const DISPLAY_TIME: u8 = 0x87; // followed by hours and minutes, not sure if this works
+    const DISPLAY_WORD: u8 = 0x88; // followed by the value, high byte first
     const POSITION_SETTING: u8 = 0x89;
-    const _FIRMWARE_REV: u8 = 0x8a;
-    const _NUMBER_DIGITS: u8 = 0x8b;
+    const FIRMWARE_REV: u8 = 0x8a;
+    const NUMBER_DIGITS: u8 = 0x8b;
     const DISPLAY_ADDRESS: u8 = 0x90;
 }
 
+/// Segment bits for raw segment masks and custom characters
+///
+/// ```text
+///      A
+///    F   B
+///      G
+///    E   C
+///      D   DP
+/// ```
+pub struct SegmentMask;
+
+impl SegmentMask {
+    /// Top segment
+    pub const SEG_A: u8 = 0b0000_0001;
+    /// Upper right segment
+    pub const SEG_B: u8 = 0b0000_0010;
+    /// Lower right segment
+    pub const SEG_C: u8 = 0b0000_0100;
+    /// Bottom segment
+    pub const SEG_D: u8 = 0b0000_1000;
+    /// Lower left segment
+    pub const SEG_E: u8 = 0b0001_0000;
+    /// Upper left segment
+    pub const SEG_F: u8 = 0b0010_0000;
+    /// Middle segment
+    pub const SEG_G: u8 = 0b0100_0000;
+    /// Decimal point
+    pub const SEG_DP: u8 = 0b1000_0000;
+}
+
+/// Gregorian leap year: divisible by 4, except centuries not divisible by 400
+fn is_leap_year(year: u16) -> bool {
+    year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400))
+}
+
+/// Uppercase ASCII letters a-z, leaving other characters as they are
+fn fold_upper(ch: char) -> char {
+    match ch {
+        'a'..='z' => (ch as u8 - b'a' + b'A') as char,
+        _ => ch,
+    }
+}
+
+/// Segment masks (see `SegmentMask`) of the characters that are legible on 7 segments
+pub const CHAR_SEGMENTS: [(char, u8); 37] = [
+    ('0', 0x3f),
+    ('1', 0x06),
+    ('2', 0x5b),
+    ('3', 0x4f),
+    ('4', 0x66),
+    ('5', 0x6d),
+    ('6', 0x7d),
+    ('7', 0x07),
+    ('8', 0x7f),
+    ('9', 0x6f),
+    ('A', 0x77),
+    ('b', 0x7c),
+    ('C', 0x39),
+    ('c', 0x58),
+    ('d', 0x5e),
+    ('E', 0x79),
+    ('F', 0x71),
+    ('G', 0x3d),
+    ('H', 0x76),
+    ('h', 0x74),
+    ('I', 0x30),
+    ('J', 0x1e),
+    ('L', 0x38),
+    ('n', 0x54),
+    ('o', 0x5c),
+    ('P', 0x73),
+    ('q', 0x67),
+    ('r', 0x50),
+    ('S', 0x6d),
+    ('t', 0x78),
+    ('U', 0x3e),
+    ('u', 0x1c),
+    ('y', 0x6e),
+    ('-', 0x40),
+    ('_', 0x08),
+    ('=', 0x48),
+    (' ', 0x00),
+];
+
+/// Rotate a segment mask (see `SegmentMask`) by 180 degrees, e.g. for displays mounted upside down:
+/// segments A and D, B and E, C and F are swapped, G stays in the middle.
+/// E.g. '2' (`0x5b`) is unchanged and '6' (`0x7d`) becomes '9' (`0x6f`). The decimal point
+/// can't be moved to the top left corner and is kept as it is.
+pub const fn segment_flip(mask: u8) -> u8 {
+    let low = mask & 0b0000_0111; // A, B, C
+    let high = mask & 0b0011_1000; // D, E, F
+
+    (mask & 0b1100_0000) | (low << 3) | (high >> 3)
+}
+
+/// Get the segment mask of a character legible on 7 segments, e.g. for `define_custom_char()`
+pub fn char_segments(ch: char) -> Option<u8> {
+    CHAR_SEGMENTS.iter().find(|(c, _)| *c == ch).map(|(_, mask)| *mask)
+}
+
+/// Check if a character is legible on 7 segments.
+/// This is a subset of the characters accepted by the display, see `is_displayable()`.
+pub fn char_is_supported(ch: char) -> bool {
+    char_segments(ch).is_some()
+}
+
+/// Iterate over the characters legible on 7 segments
+pub fn supported_chars() -> impl Iterator<Item = char> {
+    CHAR_SEGMENTS.iter().map(|(ch, _)| *ch)
+}
+
 /// Default I2C address for the device
 pub const DEFAULT_ADDRESS: u8 = 0x12;
 
+/// Time (in milliseconds) given to the device to finish clearing the display
+/// before `clear_display_confirmed()` returns
+pub const CLEAR_SETTLE_MS: u32 = 5;
+
+/// Number of digits of the largest TWIDisplay variant
+pub const MAX_DIGITS: u8 = 8;
+
+/// Rough estimate of the lowest brightness that keeps the display lit. It was found by trying,
+/// not taken from a datasheet, and depends on the panel: some units go dark above it, others stay lit below.
+pub const MIN_VISIBLE_BRIGHTNESS: u8 = 16;
+
+/// Index of the leftmost dot, after the digit at position 0
+pub const DOT_LEFT: u8 = 0;
+
+/// Index of the dot after the digit at position 1, the separator in HH.MM and MM.SS
+pub const DOT_CENTER_LEFT: u8 = 1;
+
+/// Index of the dot after the digit at position 2
+pub const DOT_CENTER_RIGHT: u8 = 2;
+
+/// Index of the rightmost dot, after the digit at position 3
+pub const DOT_RIGHT: u8 = 3;
+
+/// Position of a digit on the display, 0-3 from left to right.
+///
+/// `Position::new()` and the `P0`-`P3` constants only give valid positions of a 4-digit unit.
+/// Functions taking a position also accept a plain `u8`, e.g. for positions computed at runtime
+/// or positions 4-7 of 8-digit units, which are checked when used.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Position(u8);
+
+impl Position {
+    /// Leftmost position
+    pub const P0: Position = Position(0);
+    /// Position 1
+    pub const P1: Position = Position(1);
+    /// Position 2
+    pub const P2: Position = Position(2);
+    /// Rightmost position of a 4-digit unit
+    pub const P3: Position = Position(3);
+
+    /// Create a position from 0-3, `None` for other values
+    pub const fn new(position: u8) -> Option<Position> {
+        match position {
+            0..=3 => Some(Position(position)),
+            _ => None,
+        }
+    }
+
+    /// Index of the position, 0 being the leftmost one
+    pub const fn index(self) -> u8 {
+        self.0
+    }
+}
+
+/// Position given as a plain number, checked by the driver when used
+impl From<u8> for Position {
+    fn from(position: u8) -> Self {
+        Position(position)
+    }
+}
+
+impl From<Position> for u8 {
+    fn from(position: Position) -> Self {
+        position.0
+    }
+}
+
 /// Possible choices for temperature units
 #[allow(non_camel_case_types)]
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum TempUnits {
     /// Celsius degrees
     Celsius,
@@ -242,9 +1267,34 @@ pub enum TempUnits {
     Fahrenheit,
 }
 
+/// What is shown for a value beyond its thresholds, see `display_temperature_clamped()`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ClampBehavior {
+    /// `-LL-` below the lower threshold, `-HH-` above the upper one, as with `display_temperature()`
+    ShowMarker,
+    /// The value clamped to the threshold
+    Clamp,
+    /// Nothing, the display is blanked and the dots turned off
+    Blank,
+}
+
+/// Direction of a value, e.g. the temperature since the last reading
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Trend {
+    /// Going up
+    Rising,
+    /// Going down
+    Falling,
+    /// Not changing
+    Steady,
+}
+
 /// Possible choices for date format
 #[allow(non_camel_case_types)]
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum DateFormat {
     /// Month Day
     MMDD,
@@ -252,8 +1302,20 @@ pub enum DateFormat {
     DDMM,
 }
 
-#[allow(non_camel_case_types)]
+/// Alignment of numbers shorter than four digits
 #[derive(Copy, Clone, Debug)]
+pub enum Alignment {
+    /// Flush left, padded with spaces on the right
+    Left,
+    /// Flush right, padded with spaces on the left
+    Right,
+    /// Centered, with the odd space (if any) on the right
+    Center,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 /// Two possible display modes
 pub enum Mode {
     /// Scroll
@@ -262,21 +1324,569 @@ pub enum Mode {
     Rotate,
 }
 
+/// Startup settings applied with `init()`
+#[derive(Copy, Clone, Debug)]
+pub struct Config {
+    /// Display brightness (0 - 255)
+    pub brightness: u8,
+    /// Display mode
+    pub mode: Mode,
+    /// Clear the display first
+    pub clear: bool,
+}
+
+impl Default for Config {
+    /// Full brightness, `Rotate` mode, cleared display
+    fn default() -> Self {
+        Config {
+            brightness: 255,
+            mode: Mode::Rotate,
+            clear: true,
+        }
+    }
+}
+
+/// Driver operations, used to estimate the bus load with `transaction_count()`
+#[derive(Copy, Clone, Debug)]
+pub enum Operation {
+    /// `init()`, with the `clear` setting given
+    Init(bool),
+    /// `reset()`
+    Reset,
+    /// `clear_display()` or `clear_display_confirmed()`
+    ClearDisplay,
+    /// `set_address()`
+    SetAddress,
+    /// `set_address_unchecked()`
+    SetAddressUnchecked,
+    /// `change_address_now()`
+    ChangeAddressNow,
+    /// `reset_to_default_address()`
+    ResetToDefaultAddress,
+    /// `display_address()`
+    DisplayAddress,
+    /// `set_brightness()`
+    SetBrightness,
+    /// `set_brightness_percent()`
+    SetBrightnessPercent,
+    /// `set_mode()`
+    SetMode,
+    /// `display_dots()`
+    DisplayDots,
+    /// `set_colon()`
+    SetColon,
+    /// `set_dot()`
+    SetDot,
+    /// `send_digit()`
+    SendDigit,
+    /// `send_hex_digit()`
+    SendHexDigit,
+    /// `display_digit()`
+    DisplayDigit,
+    /// `display_two_digits()`
+    DisplayTwoDigits,
+    /// `display_digits()`
+    DisplayDigits,
+    /// `display_chars()`
+    DisplayChars,
+    /// `display_hex_digit()`
+    DisplayHexDigit,
+    /// `display_hex()`
+    DisplayHex,
+    /// `display_number()`
+    DisplayNumber,
+    /// `display_word()`
+    DisplayWord,
+    /// `display_long_number()`
+    DisplayLongNumber,
+    /// `detect_digits()`
+    DetectDigits,
+    /// `display_number_padded()`
+    DisplayNumberPadded,
+    /// `display_number_aligned()`
+    DisplayNumberAligned,
+    /// `display_number_saturating()`
+    DisplayNumberSaturating,
+    /// `display_clamped()`
+    DisplayClamped,
+    /// `display_signed()`
+    DisplaySigned,
+    /// `display_float()`
+    DisplayFloat,
+    /// `display_fixed()`
+    DisplayFixed,
+    /// `send_char()`
+    SendChar,
+    /// `display_char()`
+    DisplayChar,
+    /// `clear_digit()`
+    ClearDigit,
+    /// `send_text()` with the given number of characters
+    SendText(usize),
+    /// `send_text_upper()` with the given number of characters
+    SendTextUpper(usize),
+    /// `send_text_sanitized()` with the given number of characters
+    SendTextSanitized(usize),
+    /// `redraw_text()`
+    RedrawText,
+    /// `commit_frame()`
+    CommitFrame,
+    /// `display_number_with_dots()`
+    DisplayNumberWithDots,
+    /// `display_text_with_dots()`
+    DisplayTextWithDots,
+    /// `display_text_fixed()`
+    DisplayTextFixed,
+    /// `display_static_text()`
+    DisplayStaticText,
+    /// `scroll_text()` with the given number of characters
+    ScrollText(usize),
+    /// `flush()`
+    Flush,
+    /// `DisplayWriter::flush()`
+    WriterFlush,
+    /// `IdleDimmer::tick()`
+    DimmerTick,
+    /// `IdleDimmer::activity()`
+    DimmerActivity,
+    /// `write_frame()`
+    WriteFrame,
+    /// `play_frames()` with the given number of frames, played once
+    PlayFrames(usize),
+    /// `define_custom_char()`
+    DefineCustomChar,
+    /// `display_time()`
+    DisplayTime,
+    /// `display_time_native()`
+    DisplayTimeNative,
+    /// `display_time_padded()`
+    DisplayTimePadded,
+    /// `display_clock_tuple()`
+    DisplayClockTuple,
+    /// `display_time_12h()`
+    DisplayTime12h,
+    /// `display_stopwatch()`
+    DisplayStopwatch,
+    /// `display_duration()`
+    DisplayDuration,
+    /// `display_countdown()`
+    DisplayCountdown,
+    /// `display_clock_toggle()`
+    DisplayClockToggle,
+    /// `display_date()`
+    DisplayDate,
+    /// `display_date_checked()`
+    DisplayDateChecked,
+    /// `display_year()`
+    DisplayYear,
+    /// `display_temperature()`
+    DisplayTemperature,
+    /// `display_temperature_clamped()`
+    DisplayTemperatureClamped,
+    /// `display_temperature_with_trend()`
+    DisplayTemperatureWithTrend,
+    /// `display_temperature_hyst()`
+    DisplayTemperatureHyst,
+    /// `display_temperature_converted()`
+    DisplayTemperatureConverted,
+    /// `display_temperature_decimal()`
+    DisplayTemperatureDecimal,
+    /// `display_humidity()`
+    DisplayHumidity,
+    /// `scroll_range()`
+    ScrollRange,
+    /// `display_octet()`
+    DisplayOctet,
+    /// `display_battery()`
+    DisplayBattery,
+    /// `display_percentage()`
+    DisplayPercentage,
+    /// `display_bar()`
+    DisplayBar,
+    /// `spinner_frame()`
+    SpinnerFrame,
+    /// `display_value_with_unit()`
+    DisplayValueWithUnit,
+    /// `display_value_unit2()`
+    DisplayValueUnit2,
+    /// `seconds_pip()`
+    SecondsPip,
+    /// `display_code()`
+    DisplayCode,
+    /// `brightness_flash()`
+    BrightnessFlash,
+    /// `redraw()`
+    Redraw,
+    /// `restore()`
+    Restore,
+    /// `blink()` with the given number of times
+    Blink(u8),
+    /// `fade_brightness()` with the given number of steps
+    FadeBrightness(u16),
+    /// `try_new()`
+    TryNew,
+    /// `is_present()`
+    IsPresent,
+    /// `send_raw()`
+    SendRaw,
+    /// `get_firmware_rev()`
+    GetFirmwareRev,
+    /// `get_number_digits()`
+    GetNumberDigits,
+    /// `demo()`
+    Demo,
+    /// `self_test()`
+    SelfTest,
+}
+
+/// Maximum number of I2C transactions issued by an operation
+/// (fewer are issued if the operation fails on invalid input data)
+pub const fn transaction_count(op: Operation) -> usize {
+    match op {
+        Operation::ClearDisplay
+        | Operation::SetAddress
+        | Operation::SetAddressUnchecked
+        | Operation::ChangeAddressNow
+        | Operation::ResetToDefaultAddress
+        | Operation::DisplayAddress
+        | Operation::SetBrightness
+        | Operation::SetBrightnessPercent
+        | Operation::SetMode
+        | Operation::DisplayDots
+        | Operation::SetColon
+        | Operation::SetDot
+        | Operation::SendDigit
+        | Operation::SendHexDigit
+        | Operation::DisplayDigit
+        | Operation::DisplayTwoDigits
+        | Operation::DisplayDigits
+        | Operation::DisplayChars
+        | Operation::DisplayHexDigit
+        | Operation::DisplayHex
+        | Operation::DisplayNumberPadded
+        | Operation::DisplayNumberAligned
+        | Operation::DisplayNumber
+        | Operation::DisplayLongNumber
+        | Operation::DisplayWord
+        | Operation::DisplayTimeNative
+        | Operation::SendChar
+        | Operation::DisplayChar
+        | Operation::ClearDigit
+        | Operation::RedrawText
+        | Operation::DisplayTextFixed
+        | Operation::DisplayStaticText
+        | Operation::WriterFlush
+        | Operation::WriteFrame
+        | Operation::DefineCustomChar
+        | Operation::DisplayBattery
+        | Operation::DisplayPercentage
+        | Operation::DisplayBar
+        | Operation::SpinnerFrame
+        | Operation::DisplayValueUnit2
+        | Operation::DisplayClamped
+        | Operation::DisplaySigned
+        | Operation::SecondsPip
+        | Operation::DisplayCode
+        | Operation::SendRaw
+        | Operation::GetFirmwareRev
+        | Operation::TryNew
+        | Operation::IsPresent
+        | Operation::GetNumberDigits
+        | Operation::DetectDigits => 1,
+        // one per position
+        Operation::DisplayTemperature
+        | Operation::DisplayTemperatureHyst
+        | Operation::DisplayTemperatureClamped
+        | Operation::DisplayTemperatureConverted
+        | Operation::DisplayValueWithUnit
+        | Operation::DisplayHumidity => 4,
+        // one per position and dots
+        Operation::DisplayTemperatureWithTrend => 5,
+        Operation::SendText(chars)
+        | Operation::SendTextUpper(chars)
+        | Operation::SendTextSanitized(chars) => chars,
+        Operation::PlayFrames(frames) => frames,
+        // one per step, text shorter than the display is written at once
+        Operation::ScrollText(chars) if chars < 4 => 1,
+        Operation::ScrollText(chars) => chars + 3,
+        // clear, mode and brightness
+        Operation::Init(true) => 3,
+        Operation::Init(false) => 2,
+        // clear, dots, mode and brightness
+        Operation::Reset => 4,
+        // peak and restored brightness
+        Operation::BrightnessFlash => 2,
+        // positions, dots and brightness
+        Operation::Restore => 3,
+        // off and on for each time
+        Operation::Blink(times) => times as usize * 2,
+        // starting value and one per step
+        Operation::FadeBrightness(0) => 1,
+        Operation::FadeBrightness(steps) => steps as usize + 1,
+        // positions and dots
+        Operation::Flush
+        | Operation::Redraw
+        | Operation::CommitFrame
+        | Operation::DisplayNumberWithDots
+        | Operation::DisplayTextWithDots => 2,
+        // number and dots
+        Operation::DisplayTime
+        | Operation::DisplayNumberSaturating
+        | Operation::DisplayOctet
+        | Operation::DisplayTime12h
+        | Operation::DisplayFloat
+        | Operation::DisplayFixed
+        | Operation::DisplayTemperatureDecimal
+        | Operation::DisplayStopwatch
+        | Operation::DisplayCountdown
+        | Operation::DisplayDuration
+        | Operation::DisplayClockTuple
+        | Operation::DisplayClockToggle
+        | Operation::DisplayDate
+        | Operation::DisplayDateChecked
+        | Operation::DisplayYear
+        | Operation::DisplayTimePadded => 2,
+        // two temperatures and the separator
+        Operation::ScrollRange => 9,
+        // the brightness, only when dimming or restoring it
+        Operation::DimmerTick | Operation::DimmerActivity => 1,
+        // every step of the fixed sequences
+        Operation::Demo => 108,
+        // segments, dots, colon, two fades of 8 steps, clear, dots and brightness
+        Operation::SelfTest => 24,
+    }
+}
+
+/// Check if a character can be shown on the display: printable ASCII from the space (0x20) up to 'y' (0x79).
+/// This includes the lowercase letters except 'z', though not all characters are legible on 7 segments.
+pub fn is_displayable(ch: char) -> bool {
+    (' '..='y').contains(&ch)
+}
+
+/// Character or raw segment mask for `display_char()` and `send_char()`.
+///
+/// `DisplayChar::new()` only accepts characters that can be displayed (see `is_displayable()`),
+/// so they are checked once, when the character is created. Plain `char`s are accepted as well
+/// and checked when used, and `u8` values are taken as raw segment masks (see `SegmentMask`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DisplayChar(Glyph);
+
+/// Content of a `DisplayChar`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+enum Glyph {
+    /// Character rendered by the firmware
+    Char(char),
+    /// Raw segment mask
+    Segments(u8),
+}
+
+impl DisplayChar {
+    /// Create a character, `None` if it can't be displayed
+    pub const fn new(ch: char) -> Option<DisplayChar> {
+        match ch {
+            ' '..='y' => Some(DisplayChar(Glyph::Char(ch))),
+            _ => None,
+        }
+    }
+
+    /// The character, `None` for a raw segment mask
+    pub const fn as_char(self) -> Option<char> {
+        match self.0 {
+            Glyph::Char(ch) => Some(ch),
+            Glyph::Segments(_) => None,
+        }
+    }
+
+    /// The raw segment mask, `None` for a character
+    pub const fn segments(self) -> Option<u8> {
+        match self.0 {
+            Glyph::Char(_) => None,
+            Glyph::Segments(mask) => Some(mask),
+        }
+    }
+}
+
+/// Character given as a plain `char`, checked by the driver when used
+impl From<char> for DisplayChar {
+    fn from(ch: char) -> Self {
+        DisplayChar(Glyph::Char(ch))
+    }
+}
+
+/// Raw segment mask, see `SegmentMask`
+impl From<u8> for DisplayChar {
+    fn from(segments: u8) -> Self {
+        DisplayChar(Glyph::Segments(segments))
+    }
+}
+
+/// Full display content composed in memory, to be written at once with `flush()`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DisplayBuffer {
+    /// Characters or digits (0-9 or 0x0-0xF) at positions 0-3
+    pub chars: [u8; 4],
+    /// Dots at positions 0-3 (true is on, false is off)
+    pub dots: [bool; 4],
+}
+
+impl DisplayBuffer {
+    /// Create an empty buffer: spaces at all positions and all dots off
+    pub fn new() -> Self {
+        DisplayBuffer {
+            chars: [b' '; 4],
+            dots: [false; 4],
+        }
+    }
+}
+
+impl Default for DisplayBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Content of a display position, as last written by the driver
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Cell {
+    /// Character or digit
+    Char(u8),
+    /// Raw segment mask
+    Segments(u8),
+}
+
+/// Threshold a value was last shown beyond, for the hysteresis
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum ThresholdState {
+    /// Value shown normally
+    Normal,
+    /// Below the lower threshold, shown as `-LL-`
+    Low,
+    /// Above the upper threshold, shown as `-HH-`
+    High,
+}
+
+/// Last content of the positions, up to `MAX_DIGITS`
+type Frame = [Option<Cell>; MAX_DIGITS as usize];
+
 /// TWIDisplay driver, that holds the I2C bus instance and the I2C address used
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct TWIDisplay<I2C> {
     /// The concrete I2C device implementation.
     i2c: I2C,
     dev_addr: u8,
+    /// Invert the raw segment masks (for boards showing inverted glyphs)
+    invert: bool,
+    /// Reverse the positions (for displays mounted upside down)
+    flip: bool,
+    /// Last brightness set
+    brightness: u8,
+    /// Last mode set
+    mode: Mode,
+    /// Last dots register value
+    dots: u8,
+    /// Last content of each position, `None` if not known
+    frame: Frame,
+    /// Number of digits of the display, 4 or 8
+    digits: u8,
+    /// Threshold state of the last temperature shown with hysteresis
+    threshold_state: ThresholdState,
+    /// Keep position and dot writes in `frame` and `dots` until `commit_frame()`
+    buffering: bool,
+    /// The dots were changed while buffering
+    dots_pending: bool,
+    /// Redraw the previous content if a composite operation fails
+    rollback_on_error: bool,
+}
+
+// not derived, a zero number of digits would reject every position
+impl<I2C: Default> Default for TWIDisplay<I2C> {
+    fn default() -> Self {
+        TWIDisplay {
+            i2c: I2C::default(),
+            dev_addr: 0,
+            invert: false,
+            flip: false,
+            brightness: 0,
+            mode: Mode::Rotate,
+            dots: 0,
+            frame: [None; MAX_DIGITS as usize],
+            digits: 4,
+            threshold_state: ThresholdState::Normal,
+            buffering: false,
+            dots_pending: false,
+            rollback_on_error: false,
+        }
+    }
 }
 
+/// TWIDisplay driver borrowing the I2C bus instead of owning it.
+///
+/// `embedded-hal-async` implements `I2c` for `&mut I2C`, so this has all the methods of `TWIDisplay`.
+pub type TWIDisplayRef<'a, I2C> = TWIDisplay<&'a mut I2C>;
+
 impl<I2C, E> TWIDisplay<I2C>
 where
     I2C: I2c<Error = E>,
 {
     /// Create a new instance of the TWIDisplay driver.    
     pub fn new(i2c: I2C, dev_addr: u8) -> Self {
-        TWIDisplay { i2c, dev_addr }
+        Self::new_with_digits(i2c, dev_addr, 4)
+    }
+
+    /// Create a new instance of the TWIDisplay driver and check that the display responds,
+    /// by reading its firmware revision. A display that doesn't respond, e.g. because of a wrong
+    /// address or wiring, gives `Error::I2C`, returned together with the I2C bus,
+    /// e.g. to retry at another address or to use the bus for another device.
+    pub async fn try_new(i2c: I2C, dev_addr: u8) -> Result<Self, (Error<E>, I2C)> {
+        let mut display = Self::new(i2c, dev_addr);
+        match display.probe().await {
+            Ok(()) => Ok(display),
+            Err(error) => Err((error, display.destroy())),
+        }
+    }
+
+    /// Create a new instance of the TWIDisplay driver for a unit with the given number of digits,
+    /// e.g. 8 for the 8-digit variant. Values outside the 4 to `MAX_DIGITS` range are clamped to it.
+    pub fn new_with_digits(i2c: I2C, dev_addr: u8, digits: u8) -> Self {
+        TWIDisplay {
+            i2c,
+            dev_addr,
+            invert: false,
+            flip: false,
+            brightness: 255,
+            mode: Mode::Rotate,
+            dots: 0,
+            frame: [None; MAX_DIGITS as usize],
+            digits: digits.clamp(4, MAX_DIGITS),
+            threshold_state: ThresholdState::Normal,
+            buffering: false,
+            dots_pending: false,
+            rollback_on_error: false,
+        }
+    }
+
+    /// Apply the startup settings: clear the display (if selected), then set the mode and the brightness
+    pub async fn init(&mut self, config: Config) -> Result<(), Error<E>> {
+        if config.clear {
+            self.clear_display().await?;
+        }
+        self.set_mode(config.mode).await?;
+        self.set_brightness(config.brightness).await?;
+        Ok(())
+    }
+
+    /// Reset the display to a known clean state: clear it, turn off all the dots and the colon,
+    /// then set the mode and the brightness of `Config::default()`
+    pub async fn reset(&mut self) -> Result<(), Error<E>> {
+        let config = Config::default();
+
+        self.clear_display().await?;
+        self.write_dots(0).await?;
+        self.set_mode(config.mode).await?;
+        self.set_brightness(config.brightness).await?;
+        Ok(())
     }
 
     /// Destroy driver instance, return I2C bus instance.
@@ -284,41 +1894,97 @@ where
         self.i2c
     }
 
+    /// Borrow the I2C bus instance, e.g. to inspect its configuration
+    pub fn i2c_ref(&self) -> &I2C {
+        &self.i2c
+    }
+
+    /// Borrow the I2C bus instance mutably, e.g. to change its frequency,
+    /// keeping the state of the driver (unlike `destroy()`)
+    pub fn i2c_mut(&mut self) -> &mut I2C {
+        &mut self.i2c
+    }
+
+    /// Send raw bytes to the display in a single I2C write, e.g. commands the driver doesn't wrap.
+    /// This is an escape hatch for advanced use: the bytes are not checked, and the content
+    /// of the positions is marked as unknown afterwards.
+    pub async fn send_raw(&mut self, bytes: &[u8]) -> Result<(), Error<E>> {
+        self.write(bytes).await?;
+        self.frame = [None; MAX_DIGITS as usize];
+        Ok(())
+    }
+
     /// Write data to the I2C bus
     async fn write(&mut self, payload: &[u8]) -> Result<(), Error<E>> {
         self.i2c.write(self.dev_addr, payload).await.map_err(Error::I2C)
     }
 
-    /*
-
-    DOESN'T SEEM TO WORK - NEED TO TEST MORE
-
-    /// Read data from the I2C bus
-    fn read(&mut self, register: u8) -> Result<u8, Error<E>> {
+    /// Read a single byte register from the I2C bus
+    async fn read_register(&mut self, register: u8) -> Result<u8, Error<E>> {
         let mut data = [0];
         self.i2c
-        .write_read(self.dev_addr, &[register], &mut data)
-        .map_err(Error::I2C)
-        .and(Ok(data[0]))
+            .write_read(self.dev_addr, &[register], &mut data)
+            .await
+            .map_err(Error::I2C)
+            .and(Ok(data[0]))
+    }
+
+    // reading a register is a plain write-read, which all HALs support,
+    // unlike the zero-length writes commonly used for probing
+
+    /// Check that the display acknowledges a register read
+    async fn probe(&mut self) -> Result<(), Error<E>> {
+        self.read_register(Register::FIRMWARE_REV).await?;
+        Ok(())
+    }
+
+    /// Check whether the display still responds, without changing its content,
+    /// e.g. to initialize it again after it was reconnected. Bus errors give `false`.
+    pub async fn is_present(&mut self) -> bool {
+        self.probe().await.is_ok()
     }
 
     /// Read the firmware revision number (currently 1)
-    pub fn get_firmware_rev(&mut self) -> Result<u8, Error<E>> {
-        let data = self.read(Register::FIRMWARE_REV)?;
+    pub async fn get_firmware_rev(&mut self) -> Result<u8, Error<E>> {
+        let data = self.read_register(Register::FIRMWARE_REV).await?;
         Ok(data)
     }
 
-    /// Read the number of digits
-    pub fn get_number_digits(&mut self) -> Result<u8, Error<E>> {
-        let data = self.read(Register::NUMBER_DIGITS)?;
+    /// Read the number of digits of the display.
+    /// The value is not cached, each call reads it from the device.
+    pub async fn get_number_digits(&mut self) -> Result<u8, Error<E>> {
+        let data = self.read_register(Register::NUMBER_DIGITS).await?;
         Ok(data)
     }
 
-     */
+    /// Read the number of digits of the display (see `get_number_digits()`) and use it
+    /// for the position bounds and number formatting from now on, returning it.
+    /// Values outside the 4 to `MAX_DIGITS` range are clamped to it.
+    pub async fn detect_digits(&mut self) -> Result<u8, Error<E>> {
+        let digits = self.get_number_digits().await?;
+        self.digits = digits.clamp(4, MAX_DIGITS);
+        Ok(self.digits)
+    }
+
+    /// Get the number of digits the driver uses, set in `new_with_digits()` or by `detect_digits()`
+    pub fn digits(&self) -> u8 {
+        self.digits
+    }
 
     /// Clear the display
     pub async fn clear_display(&mut self) -> Result<(), Error<E>> {
-        self.write(&[Register::CLEAR_DISPLAY]).await?;
+        self.write(&commands::encode_clear()).await?;
+        self.frame = [Some(Cell::Char(b' ')); MAX_DIGITS as usize];
+        Ok(())
+    }
+
+    // the device gives no feedback once the display is cleared,
+    // so instead of reading back we wait for CLEAR_SETTLE_MS
+
+    /// Clear the display and wait until it is ready to accept further commands
+    pub async fn clear_display_confirmed<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        self.clear_display().await?;
+        delay.delay_ms(CLEAR_SETTLE_MS).await;
         Ok(())
     }
 
@@ -327,132 +1993,1168 @@ where
     // SEEMS TO WORK OK UP TO 0x39
     // -- USE ADDRESS 0x00 TO RESET IN CASE OF PROBLEMS
 
-    /// Set I2C address, defaults to 0x12
+    /// Set I2C address, defaults to 0x12. Addresses from 0x40 up are rejected.
+    /// The driver keeps using the current address, as the new one is only active after a power cycle.
     pub async fn set_address(&mut self, address: u8) -> Result<(), Error<E>> {
         //let mut dev_address = DEFAULT_ADDRESS;
         match address {
             //a if a < 0x7f => self.write(&[Register::I2C_ADDRESS_SETTING, a])?,
-            a if a < 0x40 => self.write(&[Register::I2C_ADDRESS_SETTING, a]).await?,
-            _ => (),
+            a if a < 0x40 => self.write(&commands::encode_address(a)).await?,
+            a => return Err(Error::AddressOutOfRange(a)),
         }
         Ok(())
     }
 
+    /// Set any I2C address, without checking the range, e.g. to experiment with addresses from 0x40 up.
+    /// The driver keeps using the current address, as the new one is only active after a power cycle.
+    pub async fn set_address_unchecked(&mut self, address: u8) -> Result<(), Error<E>> {
+        self.write(&commands::encode_address(address)).await
+    }
+
+    /// Set I2C address as with `set_address()`, and talk to the display on the new address from now on.
+    /// Only use it with displays applying the change immediately: the TWIDisplay normally keeps
+    /// the old address until it's power cycled, and won't respond anymore until then.
+    pub async fn change_address_now(&mut self, address: u8) -> Result<(), Error<E>> {
+        self.set_address(address).await?;
+        self.dev_addr = address;
+        Ok(())
+    }
+
+    /// Set the address back to the default 0x12, e.g. to recover a display whose address
+    /// doesn't work. Such a display still responds on address 0x00, so the driver must be
+    /// created with address 0x00 first. The default address is active after a power cycle.
+    pub async fn reset_to_default_address(&mut self) -> Result<(), Error<E>> {
+        self.set_address(DEFAULT_ADDRESS).await
+    }
+
+    /// Get the I2C address the driver talks to
+    pub fn address(&self) -> u8 {
+        self.dev_addr
+    }
+
     /// Show the current I2C address on the display
     pub async fn display_address(&mut self) -> Result<(), Error<E>> {
-        self.write(&[Register::DISPLAY_ADDRESS]).await?;
+        self.write(&commands::encode_display_address()).await?;
+        self.frame = [None; MAX_DIGITS as usize];
+        Ok(())
+    }
+
+    /// Set display brightness (0 - 255, 127 is 50%)
+    pub async fn set_brightness(&mut self, brightness: u8) -> Result<(), Error<E>> {
+        self.write_brightness(brightness).await?;
+        self.brightness = brightness;
+        Ok(())
+    }
+
+    /// Set display brightness as a percentage (0 - 100), mapped linearly to 0 - 255
+    /// and rounded down, so 50% is 127 and 100% is 255. Values above 100 are rejected.
+    /// Note that low percentages may leave the display dark (see `MIN_VISIBLE_BRIGHTNESS`).
+    pub async fn set_brightness_percent(&mut self, percent: u8) -> Result<(), Error<E>> {
+        if percent > 100 {
+            return Err(Error::ValueOutOfRange(percent));
+        }
+
+        self.set_brightness((percent as u16 * 255 / 100) as u8).await
+    }
+
+    /// Get the last brightness set with `set_brightness()`
+    /// (full brightness is assumed until it's called for the first time)
+    pub fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
+    /// Raise the brightness to `peak` for `duration_ms` milliseconds,
+    /// then restore the last brightness set with `set_brightness()`
+    pub async fn brightness_flash<D: DelayNs>(
+        &mut self,
+        peak: u8,
+        duration_ms: u32,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        self.write_brightness(peak).await?;
+        delay.delay_ms(duration_ms).await;
+        self.write_brightness(self.brightness).await?;
+        Ok(())
+    }
+
+    /// Flash the whole display `times` times, e.g. for an alert: turn it off for `off_ms`
+    /// milliseconds, then back on at the last brightness set with `set_brightness()` for `on_ms`.
+    /// Only the brightness is changed, so the content of the display is kept.
+    pub async fn blink<D: DelayNs>(
+        &mut self,
+        times: u8,
+        on_ms: u32,
+        off_ms: u32,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        for _ in 0..times {
+            self.write_brightness(0).await?;
+            delay.delay_ms(off_ms).await;
+            self.write_brightness(self.brightness).await?;
+            delay.delay_ms(on_ms).await;
+        }
+        Ok(())
+    }
+
+    /// Fade the brightness linearly from `from` to `to` in the given number of steps,
+    /// waiting `step_ms` milliseconds between them, e.g. for an alarm clock that brightens gently.
+    /// If `from` and `to` are equal or `steps` is 0, `to` is set at once.
+    pub async fn fade_brightness<D: DelayNs>(
+        &mut self,
+        from: u8,
+        to: u8,
+        steps: u16,
+        step_ms: u32,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        if from == to || steps == 0 {
+            return self.set_brightness(to).await;
+        }
+
+        self.set_brightness(from).await?;
+
+        for step in 1..=steps as i32 {
+            delay.delay_ms(step_ms).await;
+            let brightness = from as i32 + (to as i32 - from as i32) * step / steps as i32;
+            self.set_brightness(brightness as u8).await?;
+        }
+        Ok(())
+    }
+
+    /// Change the brightness without updating the cached value, for temporary effects
+    async fn write_brightness(&mut self, brightness: u8) -> Result<(), Error<E>> {
+        self.write(&commands::encode_brightness(brightness)).await
+    }
+
+    /// Display the dots, with boolean switches (true is on, false is off).
+    /// The colon set with `set_colon()` is kept as it is.
+    pub async fn display_dots(&mut self, dots: [bool; 4]) -> Result<(), Error<E>> {
+        let colon = self.dots & commands::COLON_BIT;
+
+        self.write_dots(commands::dots_mask(dots) | colon).await?;
+        Ok(())
+    }
+
+    /// Turn the center colon of clock displays on or off, leaving the four dots as they are,
+    /// e.g. to blink it every second. Displays without a colon ignore it.
+    pub async fn set_colon(&mut self, on: bool) -> Result<(), Error<E>> {
+        let dotvalues = match on {
+            true => self.dots | commands::COLON_BIT,
+            false => self.dots & !commands::COLON_BIT,
+        };
+
+        self.write_dots(dotvalues).await?;
+        Ok(())
+    }
+
+    /// Turn the dot at position P on or off, leaving the other dots as they are.
+    /// The driver keeps track of the dots it has written, so their state doesn't have to be known.
+    pub async fn set_dot(&mut self, position: u8, on: bool) -> Result<(), Error<E>> {
+        if position > 3 {
+            return Err(Error::PositionOutOfRange(position));
+        }
+
+        let bit = commands::dot_bit(position);
+
+        let dotvalues = match on {
+            true => self.dots | bit,
+            false => self.dots & !bit,
+        };
+
+        self.write_dots(dotvalues).await?;
+        Ok(())
+    }
+
+    /// Write the dots register value and remember it
+    async fn write_dots(&mut self, dotvalues: u8) -> Result<(), Error<E>> {
+        if self.buffering {
+            self.dots_pending = true;
+        } else {
+            self.write(&commands::encode_dots(dotvalues)).await?;
+        }
+        self.dots = dotvalues;
+        Ok(())
+    }
+
+    /// Show the seconds as the rightmost dot, which is on for even seconds and off for odd ones.
+    /// The digits and the other dots are not affected, e.g. on an HH.MM clock.
+    pub async fn seconds_pip(&mut self, seconds: u8) -> Result<(), Error<E>> {
+        if seconds > 59 {
+            return Err(Error::ValueOutOfRange(seconds));
+        }
+
+        self.set_dot(DOT_RIGHT, seconds.is_multiple_of(2)).await?;
+        Ok(())
+    }
+
+    /// Send a digit to the display without specifying the position
+    pub async fn send_digit(&mut self, number: u8) -> Result<(), Error<E>> {
+        if number > 9 {
+            return Err(Error::DigitOutOfRange(number));
+        } else {
+            self.write(&[number]).await?
+        };
+        // the position depends on the display mode
+        self.frame = [None; MAX_DIGITS as usize];
+        Ok(())
+    }
+
+    /// Send a hexadecimal digit (0x0 - 0xF) to the display without specifying the position
+    pub async fn send_hex_digit(&mut self, value: u8) -> Result<(), Error<E>> {
+        if value > 0x0f {
+            return Err(Error::DigitOutOfRange(value));
+        }
+
+        self.write(&[value]).await?;
+        // the position depends on the display mode
+        self.frame = [None; MAX_DIGITS as usize];
+        Ok(())
+    }
+
+    /// Write digit D at position P
+    pub async fn display_digit(&mut self, position: impl Into<Position>, digit: u8) -> Result<(), Error<E>> {
+        let position = position.into().index();
+
+        if position >= self.digits {
+            return Err(Error::PositionOutOfRange(position));
+        } else if digit > 9 {
+            return Err(Error::DigitOutOfRange(digit));
+        } else if !self.buffering {
+            self.write(&self.encode_char(position, digit)).await?
+        };
+
+        self.frame[position as usize] = Some(Cell::Char(digit));
+        Ok(())
+    }
+
+    /// Write a value (0 - 99) with a leading zero at positions P and P+1 in one transaction,
+    /// e.g. 7 at position 2 is shown as `07` on positions 2 and 3. The other positions are left as they are.
+    pub async fn display_two_digits(&mut self, start_position: impl Into<Position>, value: u8) -> Result<(), Error<E>> {
+        let position = start_position.into().index();
+
+        // compared before adding anything, as the position may be up to 255
+        if position >= self.digits - 1 {
+            return Err(Error::PositionOutOfRange(position));
+        } else if value > 99 {
+            return Err(Error::NumberTooLarge(value.into()));
+        }
+
+        let digits = [value / 10, value % 10];
+        let mut payload = [0u8; 6];
+        payload[..3].copy_from_slice(&self.encode_char(position, digits[0]));
+        payload[3..].copy_from_slice(&self.encode_char(position + 1, digits[1]));
+
+        if !self.buffering {
+            self.write(&payload).await?;
+        }
+        for (cell, digit) in self.frame[position as usize..].iter_mut().zip(digits) {
+            *cell = Some(Cell::Char(digit));
+        }
+        Ok(())
+    }
+
+    /// Write four digits (0 - 9) at positions 0 - 3 in one transaction.
+    /// Nothing is written if any of the digits is out of range.
+    pub async fn display_digits(&mut self, digits: [u8; 4]) -> Result<(), Error<E>> {
+        if let Some(&digit) = digits.iter().find(|&&digit| digit > 9) {
+            return Err(Error::DigitOutOfRange(digit));
+        }
+
+        self.write_chars(&digits).await
+    }
+
+    /// Write four characters at positions 0 - 3 in one transaction, like `display_code()`.
+    /// Nothing is written if any of the characters can't be displayed (see `is_displayable()`).
+    pub async fn display_chars(&mut self, chars: [char; 4]) -> Result<(), Error<E>> {
+        self.display_code(chars).await
+    }
+
+    // 0x00 - 0x0f: Displays a single digit 0-9 or hexadecimal digit A-F.
+
+    /// Write hexadecimal digit (0x0 - 0xF) at position P
+    pub async fn display_hex_digit(&mut self, position: impl Into<Position>, value: u8) -> Result<(), Error<E>> {
+        let position = position.into().index();
+
+        if position >= self.digits {
+            return Err(Error::PositionOutOfRange(position));
+        }
+        if value > 0x0f {
+            return Err(Error::DigitOutOfRange(value));
+        } else if !self.buffering {
+            self.write(&self.encode_char(position, value)).await?
+        };
+
+        self.frame[position as usize] = Some(Cell::Char(value));
+        Ok(())
+    }
+
+    /// Display a 16-bit value as four hexadecimal digits, including leading zeros (e.g. `00FF`),
+    /// in a single I2C transaction
+    pub async fn display_hex(&mut self, value: u16) -> Result<(), Error<E>> {
+        let nibbles = [
+            (value >> 12) as u8 & 0x0f,
+            (value >> 8) as u8 & 0x0f,
+            (value >> 4) as u8 & 0x0f,
+            value as u8 & 0x0f,
+        ];
+
+        self.write_chars(&nibbles).await?;
+        Ok(())
+    }
+
+    /// Display a number using all four digits, in a single I2C transaction.
+    /// All four positions are always written, including zeros, so no stale digits are left over
+    /// when e.g. a clock rolls over from `12.59` to `13.00`.
+    pub async fn display_number(&mut self, number: u16) -> Result<(), Error<E>> {
+        self.display_long_number(number.into()).await
+    }
+
+    // NOT VERIFIED ON HARDWARE: THE BYTE ORDER (HIGH BYTE FIRST) IS AN ASSUMPTION,
+    // COMPARE WITH display_number() BEFORE RELYING ON IT
+
+    /// Display a number up to 9999 with the native DISPLAY_WORD command of the firmware,
+    /// in a single 3-byte write. The layout, e.g. leading zeros, is decided by the firmware.
+    pub async fn display_word(&mut self, value: u16) -> Result<(), Error<E>> {
+        if value > 9999 {
+            return Err(Error::NumberTooLarge(value.into()));
+        }
+
+        self.write(&commands::encode_word(value)).await?;
+        // rendered by the firmware
+        self.frame = [None; MAX_DIGITS as usize];
+        Ok(())
+    }
+
+    /// Display a number using all the digits of the display, in a single I2C transaction:
+    /// up to 9999 on 4-digit units and up to 99999999 on 8-digit units (see `new_with_digits()`),
+    /// with leading zeros, e.g. `00012345`.
+    pub async fn display_long_number(&mut self, number: u32) -> Result<(), Error<E>> {
+        if number >= 10_u32.pow(self.digits as u32) {
+            return Err(Error::NumberTooLarge(number.into()));
+        }
+
+        let digits = commands::get_long_digits(number);
+
+        self.write_positions(&digits[(MAX_DIGITS - self.digits) as usize..]).await
+    }
+
+    /// Display a number using all the digits of the display like `display_long_number()`,
+    /// saturating at the highest number it can show (9999, or 99999999 on 8-digit units).
+    /// The rightmost dot (`DOT_RIGHT`) is lit when the number was saturated and turned off otherwise.
+    pub async fn display_number_saturating(&mut self, number: u32) -> Result<(), Error<E>> {
+        let max_number = 10_u32.pow(self.digits as u32) - 1;
+
+        self.composite(async |display| {
+            display.display_long_number(number.min(max_number)).await?;
+            display.set_dot(DOT_RIGHT, number > max_number).await
+        })
+        .await
+    }
+
+    /// Display a number using all four digits, with or without leading zeros (e.g. `0023` or `  23`),
+    /// in a single I2C transaction
+    pub async fn display_number_padded(&mut self, number: u16, leading_zeros: bool) -> Result<(), Error<E>> {
+        if number > 9999 {
+            return Err(Error::NumberTooLarge(number.into()));
+        }
+
+        let digits = commands::get_digits(number);
+
+        let chars = match leading_zeros {
+            true => digits,
+            false => commands::blank_leading_zeros(digits),
+        };
+
+        self.write_chars(&chars).await?;
+        Ok(())
+    }
+
+    /// Display a number without leading zeros, aligned left, right or centered,
+    /// e.g. `7   `, `   7` or ` 7  `, in a single I2C transaction
+    pub async fn display_number_aligned(&mut self, number: u16, align: Alignment) -> Result<(), Error<E>> {
+        if number > 9999 {
+            return Err(Error::NumberTooLarge(number.into()));
+        }
+
+        let digits = commands::get_digits(number);
+
+        // number of digits, at least one
+        let first = digits.iter().position(|d| *d != 0).unwrap_or(3);
+        let width = 4 - first;
+
+        let start = match align {
+            Alignment::Left => 0,
+            Alignment::Right => first,
+            Alignment::Center => first / 2,
+        };
+
+        let mut chars = [b' '; 4];
+        chars[start..start + width].copy_from_slice(&digits[first..]);
+
+        self.write_chars(&chars).await?;
+        Ok(())
+    }
+
+    // NOT SURE IF THE POSITION AUTO-INCREMENTS AFTER EACH DIGIT IN BOTH MODES,
+    // SO EACH DIGIT IS SENT WITH ITS OWN POSITION, STILL IN A SINGLE TRANSACTION
+
+    /// Display a number between -999 and 9999, or the overflow glyph on all four positions
+    /// if the number doesn't fit. Numbers are right-aligned, with no leading zeros
+    /// and the minus sign at the first position, e.g. `-  5` or `  42`.
+    /// An overflow glyph that can't be displayed (see `is_displayable()`) is rejected, whatever the number.
+    pub async fn display_clamped(&mut self, value: i32, overflow_glyph: char) -> Result<(), Error<E>> {
+        if !is_displayable(overflow_glyph) {
+            return Err(Error::UnsupportedChar(overflow_glyph));
+        }
+
+        if !(-999..=9999).contains(&value) {
+            self.write_chars(&[overflow_glyph as u8; 4]).await?;
+            return Ok(());
+        }
+
+        self.display_signed(value as i16).await?;
+
+        Ok(())
+    }
+
+    /// Display a number between -999 and 9999 in a single I2C transaction.
+    /// Numbers are right-aligned, with no leading zeros and the minus sign at the first position,
+    /// e.g. `-  7`, `- 42` or ` 123`.
+    /// Numbers that don't fit, e.g. -1000 which would need five positions, are rejected.
+    pub async fn display_signed(&mut self, number: i16) -> Result<(), Error<E>> {
+        if !(-999..=9999).contains(&number) {
+            return Err(Error::NumberTooLarge(number.into()));
+        }
+
+        let digits = commands::get_digits(number.unsigned_abs());
+        let mut chars = commands::blank_leading_zeros(digits);
+
+        // magnitude is at most 999 here, so the first position is free
+        if number < 0 {
+            chars[0] = b'-';
+        }
+
+        self.write_chars(&chars).await?;
+        Ok(())
+    }
+
+    /// Display a decimal number rounded to the given number of decimals (0-3),
+    /// with the dot after the last integer digit, e.g. `23.4` for 23.4 with one decimal.
+    /// Numbers are left-aligned, with the minus sign right before the digits, e.g. `-0.5`,
+    /// and the remaining positions on the right blank.
+    /// Numbers that don't fit in four positions together with the sign are rejected.
+    pub async fn display_float(&mut self, value: f32, decimals: u8) -> Result<(), Error<E>> {
+        if decimals > 3 {
+            return Err(Error::ValueOutOfRange(decimals));
+        } else if !value.is_finite() {
+            return Err(Error::InvalidInputData);
+        }
+
+        // round half away from zero, f32::round() is not available without std
+        let scaled = value * 10u16.pow(decimals as u32) as f32;
+        let scaled = if scaled < 0.0 { scaled - 0.5 } else { scaled + 0.5 };
+
+        if scaled <= -10000.0 || scaled >= 10000.0 {
+            return Err(Error::NumberTooLarge(value as i64));
+        }
+
+        // truncates towards zero, completing the rounding
+        let scaled = scaled as i16;
+        let magnitude = scaled.unsigned_abs();
+        let negative = scaled < 0;
+
+        // at least one digit before the dot, e.g. `0.5`
+        let mut width = decimals as usize + 1;
+        while width < 4 && magnitude >= 10u16.pow(width as u32) {
+            width += 1;
+        }
+
+        let sign = negative as usize;
+        if sign + width > 4 {
+            return Err(Error::NumberTooLarge(value as i64));
+        }
+
+        let digits = commands::get_digits(magnitude);
+        let mut chars = [b' '; 4];
+        if negative {
+            chars[0] = b'-';
+        }
+        chars[sign..sign + width].copy_from_slice(&digits[4 - width..]);
+
+        let mut dots = [false; 4];
+        if decimals > 0 {
+            dots[sign + width - decimals as usize - 1] = true;
+        }
+
+        self.write_chars_with_dots(&chars, dots).await
+    }
+
+    /// Display a fixed-point number with one decimal, without any floating point math:
+    /// the integer part ends at `dot_position`, where the dot is shown, and the fractional digit
+    /// (0-9) follows it, e.g. `23.4` for 23, 4 and dot position 1. Other positions are blank.
+    /// Numbers whose integer part (with the minus sign) doesn't fit before the dot are rejected.
+    pub async fn display_fixed(&mut self, integer: i16, fraction: u8, dot_position: u8) -> Result<(), Error<E>> {
+        // the last position (3) leaves no room for the fractional digit after the dot
+        if dot_position >= 3 {
+            return Err(Error::PositionOutOfRange(dot_position));
+        } else if fraction > 9 {
+            return Err(Error::DigitOutOfRange(fraction));
+        }
+
+        let magnitude = integer.unsigned_abs();
+        let mut width = 1;
+        while width < 5 && magnitude >= 10u16.pow(width as u32) {
+            width += 1;
+        }
+
+        let end = dot_position as usize + 1;
+        let start = end as isize - width as isize - (integer < 0) as isize;
+        if start < 0 {
+            return Err(Error::NumberTooLarge(integer.into()));
+        }
+
+        let digits = commands::get_digits(magnitude);
+        let mut chars = [b' '; 4];
+        if integer < 0 {
+            chars[start as usize] = b'-';
+        }
+        chars[end - width..end].copy_from_slice(&digits[4 - width..]);
+        chars[end] = fraction;
+
+        let mut dots = [false; 4];
+        dots[dot_position as usize] = true;
+
+        self.write_chars_with_dots(&chars, dots).await
+    }
+
+    /// Send a character to the display without specifying the position.
+    /// Characters that can't be displayed (see `is_displayable()`) are rejected,
+    /// as well as raw segment masks, which need a position (see `display_char()`).
+    pub async fn send_char(&mut self, ch: impl Into<DisplayChar>) -> Result<(), Error<E>> {
+        let ch = match ch.into().0 {
+            Glyph::Char(ch) => ch,
+            Glyph::Segments(_) => return Err(Error::InvalidInputData),
+        };
+
+        if !is_displayable(ch) {
+            return Err(Error::UnsupportedChar(ch));
+        }
+
+        self.write(&[ch as u8]).await?;
+        // the position depends on the display mode
+        self.frame = [None; MAX_DIGITS as usize];
+        Ok(())
+    }
+
+    /// Write character C at position P.
+    /// Characters that can't be displayed (see `is_displayable()`) are rejected.
+    /// Raw segment masks are written as with `define_custom_char()`.
+    pub async fn display_char(&mut self, position: impl Into<Position>, ch: impl Into<DisplayChar>) -> Result<(), Error<E>> {
+        let position = position.into().index();
+        let ch = match ch.into().0 {
+            Glyph::Char(ch) => ch,
+            Glyph::Segments(mask) => return self.define_custom_char(position, mask).await,
+        };
+
+        if position >= self.digits {
+            return Err(Error::PositionOutOfRange(position));
+        } else if !is_displayable(ch) {
+            return Err(Error::UnsupportedChar(ch));
+        } else if !self.buffering {
+            self.write(&self.encode_char(position, ch as u8)).await?;
+        };
+        self.frame[position as usize] = Some(Cell::Char(ch as u8));
+        Ok(())
+    }
+
+    /// Blank the digit at position P, leaving the other positions and the dots as they are
+    pub async fn clear_digit(&mut self, position: impl Into<Position>) -> Result<(), Error<E>> {
+        self.display_char(position, ' ').await
+    }
+
+    /// Send text to the display
+    pub async fn send_text(&mut self, text: &str) -> Result<(), Error<E>> {
+        for ch in text.chars() {
+            self.send_char(ch).await?
+        }
+        Ok(())
+    }
+
+    /// Send text to the display with ASCII letters a-z uppercased, e.g. `hello` is sent as `HELLO`.
+    /// Other characters are sent as they are.
+    pub async fn send_text_upper(&mut self, text: &str) -> Result<(), Error<E>> {
+        for ch in text.chars() {
+            self.send_char(fold_upper(ch)).await?
+        }
+        Ok(())
+    }
+
+    /// Send text to the display, keeping only the characters legible on 7 segments (see `char_is_supported()`):
+    /// other letters are uppercased if that makes them legible, and the remaining characters
+    /// are replaced with spaces, e.g. `Hi!€` is sent as `HI  ` and `bdM` as `bd `.
+    /// Unlike `send_text()`, this never fails on the content of the text.
+    pub async fn send_text_sanitized(&mut self, text: &str) -> Result<(), Error<E>> {
+        for ch in text.chars() {
+            let ch = match (ch, fold_upper(ch)) {
+                (ch, _) if char_is_supported(ch) => ch,
+                (_, upper) if char_is_supported(upper) => upper,
+                _ => ' ',
+            };
+            self.send_char(ch).await?
+        }
+        Ok(())
+    }
+
+    /// Scroll text across the display from right to left, one character every `step_ms` milliseconds.
+    /// The text enters from and exits into blank positions, e.g. `ABCDEF` is shown as
+    /// `   A`, `  AB`, ` ABC`, `ABCD`, `BCDE`, `CDEF`, `DEF `, `EF  `, `F   `.
+    /// Text shorter than four characters is simply displayed, without waiting.
+    /// Nothing is written if any of the characters can't be displayed.
+    pub async fn scroll_text<D: DelayNs>(&mut self, text: &str, delay: &mut D, step_ms: u32) -> Result<(), Error<E>> {
+        if let Some(ch) = text.chars().find(|ch| !is_displayable(*ch)) {
+            return Err(Error::UnsupportedChar(ch));
+        }
+
+        let len = text.chars().count();
+
+        if len < 4 {
+            return self.redraw_text(text).await;
+        }
+
+        // the window starts three positions before the text and ends three positions after it
+        for step in 0..len + 3 {
+            let mut chars = [b' '; 4];
+            for (idx, ch) in chars.iter_mut().enumerate() {
+                if let Some(text_idx) = (step + idx).checked_sub(3) {
+                    if let Some(c) = text.chars().nth(text_idx) {
+                        *ch = c as u8;
+                    }
+                }
+            }
+
+            self.write_chars(&chars).await?;
+            delay.delay_ms(step_ms).await;
+        }
+
+        Ok(())
+    }
+
+    /// Display a four-character code, e.g. a status or access code, in a single I2C transaction.
+    /// Nothing is written if any of the characters can't be displayed.
+    pub async fn display_code(&mut self, code: [char; 4]) -> Result<(), Error<E>> {
+        if let Some(ch) = code.iter().find(|ch| !is_displayable(**ch)) {
+            return Err(Error::UnsupportedChar(*ch));
+        }
+
+        self.write_chars(&code.map(|ch| ch as u8)).await?;
+        Ok(())
+    }
+
+    /// Write the whole display, padded with spaces or truncated to four characters,
+    /// in a single I2C transaction without clearing it first, like `display_text_fixed()`.
+    /// Nothing is written if any of the shown characters can't be displayed.
+    pub async fn redraw_text(&mut self, text: &str) -> Result<(), Error<E>> {
+        self.display_text_fixed(text).await
+    }
+
+    /// Display up to four characters at positions 0-3, regardless of the display mode.
+    /// Short text is padded with spaces and longer text is truncated, e.g. `AB` is shown as `AB  `
+    /// and `ABCDE` as `ABCD`. Nothing is written if any of the shown characters can't be displayed.
+    pub async fn display_text_fixed(&mut self, text: &str) -> Result<(), Error<E>> {
+        let mut chars = [b' '; 4];
+
+        for (idx, ch) in text.chars().take(4).enumerate() {
+            if !is_displayable(ch) {
+                return Err(Error::UnsupportedChar(ch));
+            }
+            chars[idx] = ch as u8;
+        }
+
+        self.write_chars(&chars).await?;
+        Ok(())
+    }
+
+    /// Display text on all the positions of the display (see `digits()`) in one transaction,
+    /// with the same result in Scroll and Rotate mode, which is left as it is.
+    /// Short text is padded with spaces and longer text is truncated, e.g. `AB` is shown as `AB  `
+    /// on a 4-digit unit and as `AB      ` on an 8-digit one.
+    /// Nothing is written if any of the shown characters can't be displayed.
+    pub async fn display_static_text(&mut self, text: &str) -> Result<(), Error<E>> {
+        let mut chars = [b' '; MAX_DIGITS as usize];
+
+        for (idx, ch) in text.chars().take(self.digits as usize).enumerate() {
+            if !is_displayable(ch) {
+                return Err(Error::UnsupportedChar(ch));
+            }
+            chars[idx] = ch as u8;
+        }
+
+        self.write_positions(&chars[..self.digits as usize]).await
+    }
+
+    /// Display a number like `display_number()` together with the dots (true is on, false is off):
+    /// the digits in one transaction, then the dots
+    pub async fn display_number_with_dots(&mut self, number: u16, dots: [bool; 4]) -> Result<(), Error<E>> {
+        self.composite(async |display| {
+            display.display_number(number).await?;
+            display.display_dots(dots).await
+        })
+        .await
+    }
+
+    /// Display text like `display_text_fixed()` together with the dots (true is on, false is off):
+    /// the characters in one transaction, then the dots
+    pub async fn display_text_with_dots(&mut self, text: &str, dots: [bool; 4]) -> Result<(), Error<E>> {
+        self.composite(async |display| {
+            display.display_text_fixed(text).await?;
+            display.display_dots(dots).await
+        })
+        .await
+    }
+
+    /// Write a buffer to the display: all four positions in one transaction, then the dots.
+    /// Nothing is written if the buffer holds a character that can't be displayed (see `is_displayable()`),
+    /// digit values (0x0-0xF) are accepted as they are.
+    pub async fn flush(&mut self, buf: &DisplayBuffer) -> Result<(), Error<E>> {
+        if let Some(&ch) = buf.chars.iter().find(|&&ch| ch > 0x0f && !is_displayable(ch as char)) {
+            return Err(Error::UnsupportedChar(ch as char));
+        }
+
+        self.write_chars_with_dots(&buf.chars, buf.dots).await
+    }
+
+    /// Write characters or digits to the first four positions in one transaction
+    async fn write_chars(&mut self, chars: &[u8; 4]) -> Result<(), Error<E>> {
+        self.write_positions(chars).await
+    }
+
+    /// Write characters or digits to the first positions, up to `MAX_DIGITS`, in one transaction
+    async fn write_positions(&mut self, chars: &[u8]) -> Result<(), Error<E>> {
+        let mut payload = [0u8; 3 * MAX_DIGITS as usize];
+        let len = chars.len().min(MAX_DIGITS as usize);
+
+        for (idx, ch) in chars.iter().take(len).enumerate() {
+            payload[idx * 3..idx * 3 + 3].copy_from_slice(&self.encode_char(idx as u8, *ch));
+        }
+
+        if !self.buffering {
+            self.write(&payload[..len * 3]).await?;
+        }
+        for (cell, ch) in self.frame.iter_mut().zip(&chars[..len]) {
+            *cell = Some(Cell::Char(*ch));
+        }
+        Ok(())
+    }
+
+    // some boards show lit segments dark and vice versa,
+    // inverting segments A-G works around it, the decimal point bit is left as is
+
+    /// Invert the segments A-G sent to the display (defaults to off).
+    /// This applies to raw segment writes and to characters legible on 7 segments
+    /// (see `CHAR_SEGMENTS`), which are written as inverted segment masks.
+    /// Other characters are rendered by the display firmware and are not inverted.
+    pub fn set_invert(&mut self, invert: bool) {
+        self.invert = invert;
+    }
+
+    /// Apply the inversion and orientation settings to a raw segment mask
+    fn segments(&self, mask: u8) -> u8 {
+        let mask = match self.invert {
+            true => mask ^ 0x7f,
+            false => mask,
+        };
+        match self.flip {
+            true => segment_flip(mask),
+            false => mask,
+        }
+    }
+
+    /// Write a character or digit (0x0 - 0xF) at a position, according to the inversion and orientation:
+    /// when inverted or flipped, characters legible on 7 segments are written as segment masks
+    fn encode_char(&self, position: u8, ch: u8) -> [u8; 3] {
+        let glyph = match ch {
+            0..=9 => (b'0' + ch) as char,
+            0x0a..=0x0f => b"AbCdEF"[ch as usize - 0x0a] as char,
+            _ => ch as char,
+        };
+
+        match char_segments(glyph) {
+            Some(mask) if self.invert || self.flip => commands::encode_custom_char(self.physical(position), self.segments(mask)),
+            _ => commands::encode_position(self.physical(position), ch),
+        }
+    }
+
+    /// Set the orientation of the display: with `flip` set, e.g. for displays mounted upside down,
+    /// the positions are reversed, so position 0 is the rightmost one (defaults to off),
+    /// and the segments are rotated with `segment_flip()`.
+    /// This applies to all the writes at positions: raw segment masks, and characters legible
+    /// on 7 segments (see `CHAR_SEGMENTS`), which are written as rotated segment masks.
+    /// Other characters are rendered upright by the display firmware, i.e. upside down.
+    /// Characters sent without a position (e.g. `send_char()`) and the dots are not affected.
+    pub fn set_orientation(&mut self, flip: bool) {
+        self.flip = flip;
+    }
+
+    /// Map a position to the position on the display, according to the orientation
+    fn physical(&self, position: u8) -> u8 {
+        match self.flip {
+            true => self.digits - 1 - position,
+            false => position,
+        }
+    }
+
+    /// Write the last known content of the positions and the dots again, e.g. after a bus error.
+    /// Positions whose content is not known (e.g. after `send_char()`) are left as they are.
+    pub async fn redraw(&mut self) -> Result<(), Error<E>> {
+        self.redraw_state(self.snapshot()).await
+    }
+
+    /// Restore the whole state the driver keeps track of: the last known content of the positions
+    /// and the dots, as with `redraw()`, and the last brightness set with `set_brightness()`,
+    /// e.g. after temporary effects like `blink()` were interrupted or the display was power cycled.
+    pub async fn restore(&mut self) -> Result<(), Error<E>> {
+        self.redraw().await?;
+        self.write_brightness(self.brightness).await
+    }
+
+    /// Enable or disable (default) redrawing the previous content when a bus error occurs
+    /// partway through an operation issuing several I2C transactions, e.g. `display_time()`,
+    /// so that a half-updated display is never left behind.
+    /// On failure this costs up to two additional transactions, whose errors are ignored.
+    pub fn set_rollback_on_error(&mut self, enabled: bool) {
+        self.rollback_on_error = enabled;
+    }
+
+    /// Current content of the positions and the dots
+    fn snapshot(&self) -> (Frame, u8) {
+        (self.frame, self.dots)
+    }
+
+    /// Run an operation issuing several transactions,
+    /// rolling back to the previous content after a bus error if enabled
+    async fn composite(
+        &mut self,
+        operation: impl AsyncFnOnce(&mut Self) -> Result<(), Error<E>>,
+    ) -> Result<(), Error<E>> {
+        let snapshot = self.snapshot();
+        let result = operation(self).await;
+        if self.rollback_on_error && matches!(result, Err(Error::I2C(_))) {
+            // the original error is more useful than a failed redraw
+            let _ = self.redraw_state(snapshot).await;
+        }
+        result
+    }
+
+    /// Write characters or digits to the first four positions, then the dots (true is on, false is off),
+    /// rolling back both after a bus error if enabled
+    async fn write_chars_with_dots(&mut self, chars: &[u8; 4], dots: [bool; 4]) -> Result<(), Error<E>> {
+        self.composite(async |display| {
+            display.write_chars(chars).await?;
+            display.display_dots(dots).await
+        })
+        .await
+    }
+
+    /// Start buffering: from now on, the positions and the dots written e.g. by `display_digit()`,
+    /// `display_char()`, `write_frame()` or `set_dot()` are only kept by the driver,
+    /// and written all at once by `commit_frame()`. Other commands, e.g. `set_brightness()`,
+    /// `clear_display()` or `send_char()`, are still sent right away.
+    pub fn begin_frame(&mut self) {
+        self.buffering = true;
+    }
+
+    /// Stop buffering and write the buffered content: all the known positions in one transaction,
+    /// then the dots if they were changed
+    pub async fn commit_frame(&mut self) -> Result<(), Error<E>> {
+        self.buffering = false;
+
+        self.write_known_positions(self.frame).await?;
+        if self.dots_pending {
+            self.write_dots(self.dots).await?;
+            self.dots_pending = false;
+        }
+        Ok(())
+    }
+
+    /// Write the given content of the positions (when known) and the dots
+    async fn redraw_state(&mut self, (frame, dots): (Frame, u8)) -> Result<(), Error<E>> {
+        self.write_known_positions(frame).await?;
+        self.write_dots(dots).await?;
+        Ok(())
+    }
+
+    /// Write the given content of the positions, skipping the unknown ones, in one transaction
+    async fn write_known_positions(&mut self, frame: Frame) -> Result<(), Error<E>> {
+        let mut payload = [0u8; 3 * MAX_DIGITS as usize];
+        let mut len = 0;
+
+        for (idx, cell) in frame.iter().take(self.digits as usize).enumerate() {
+            let command = match cell {
+                Some(Cell::Char(ch)) => self.encode_char(idx as u8, *ch),
+                Some(Cell::Segments(mask)) => commands::encode_custom_char(self.physical(idx as u8), self.segments(*mask)),
+                None => continue,
+            };
+            payload[len..len + 3].copy_from_slice(&command);
+            len += 3;
+        }
+
+        if len > 0 {
+            self.write(&payload[..len]).await?;
+            for (idx, cell) in frame.iter().enumerate() {
+                if cell.is_some() {
+                    self.frame[idx] = *cell;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Define a custom character from a segment mask (see `SegmentMask`)
+    /// and show it in the given slot, which is the position on the display (0-3, or 0-7 on 8-digit units)
+    pub async fn define_custom_char(&mut self, slot: impl Into<Position>, segments: u8) -> Result<(), Error<E>> {
+        let slot = slot.into().index();
+
+        if slot >= self.digits {
+            return Err(Error::PositionOutOfRange(slot));
+        }
+
+        if !self.buffering {
+            self.write(&commands::encode_custom_char(self.physical(slot), self.segments(segments))).await?;
+        }
+        self.frame[slot as usize] = Some(Cell::Segments(segments));
         Ok(())
     }
 
-    /// Set display brightness (0 - 255, 127 is 50%)
-    pub async fn set_brightness(&mut self, brightness: u8) -> Result<(), Error<E>> {
-        self.write(&[Register::BRIGHTNESS_SETTING, brightness]).await?;
+    // segment bits: bit 0 is segment A (top), then clockwise B, C, D (bottom), E, F,
+    // bit 6 is segment G (middle), bit 7 is the decimal point, see SegmentMask
+    // all four positions are sent as [CUSTOM_CHAR, position, segments] in one transaction
+
+    /// Write raw segment masks to all four positions at once
+    pub async fn write_frame(&mut self, segments: &[u8; 4]) -> Result<(), Error<E>> {
+        let mut payload = [0u8; 12];
+
+        for (idx, mask) in segments.iter().enumerate() {
+            payload[idx * 3..idx * 3 + 3]
+                .copy_from_slice(&commands::encode_custom_char(self.physical(idx as u8), self.segments(*mask)));
+        }
+
+        if !self.buffering {
+            self.write(&payload).await?;
+        }
+        for (cell, mask) in self.frame.iter_mut().zip(segments) {
+            *cell = Some(Cell::Segments(*mask));
+        }
         Ok(())
     }
 
-    /// Display the dots, with boolean switches (true is on, false is off)
-
-    // dots are numbered 1,2,3,4 from the left, and they correspond to bits
-    // so 0b0000_0010 is bit 1, dot 1, 0b0000_1000 is bit 3, dot 3 and so on
+    /// Play an animation: write each frame of raw segment masks (see `write_frame()`) in order,
+    /// waiting `frame_ms` milliseconds after each one. With `repeat` set to `true` the animation
+    /// starts over after the last frame and only returns on an error.
+    pub async fn play_frames<D: DelayNs>(
+        &mut self,
+        frames: &[[u8; 4]],
+        frame_ms: u32,
+        repeat: bool,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        // an empty animation would loop forever without doing anything
+        if frames.is_empty() {
+            return Ok(());
+        }
 
-    pub async fn display_dots(&mut self, dots: [bool; 4]) -> Result<(), Error<E>> {
-        let mut dotvalues: u8 = 0;
+        loop {
+            for frame in frames {
+                self.write_frame(frame).await?;
+                delay.delay_ms(frame_ms).await;
+            }
 
-        for (idx, dot) in dots.iter().enumerate() {
-            match dot {
-                true => dotvalues += 2_u8.pow(idx as u32 + 1_u32),
-                false => (),
+            if !repeat {
+                return Ok(());
             }
         }
+    }
 
-        self.write(&[Register::DOTS, dotvalues]).await?;
-        Ok(())
+    /// Display time in HH:MM format, with an optional dot between them
+    pub async fn display_time(&mut self, hours: u8, minutes: u8, dot: bool) -> Result<(), Error<E>> {
+        self.display_time_padded(hours, minutes, dot, true).await
     }
 
-    /// Send a digit to the display without specifying the position
-    pub async fn send_digit(&mut self, number: u8) -> Result<(), Error<E>> {
-        if number > 9 {
-            return Err(Error::InvalidInputData);
-        } else {
-            self.write(&[number]).await?
-        };
+    // NOT VERIFIED ON HARDWARE: THE COMMAND IS DOCUMENTED, BUT IT'S NOT KNOWN HOW THE FIRMWARE
+    // LAYS OUT THE TIME OR WHETHER IT SETS THE DOTS, display_time() IS THE PORTABLE FALLBACK
+
+    /// Display time with the native DISPLAY_TIME command of the firmware, in a single 3-byte write.
+    /// The layout is decided by the firmware, see `display_time()` for the portable version.
+    pub async fn display_time_native(&mut self, hours: u8, minutes: u8) -> Result<(), Error<E>> {
+        if hours > 23 {
+            return Err(Error::ValueOutOfRange(hours));
+        } else if minutes > 59 {
+            return Err(Error::ValueOutOfRange(minutes));
+        }
+
+        self.write(&commands::encode_time(hours, minutes)).await?;
+        // rendered by the firmware
+        self.frame = [None; MAX_DIGITS as usize];
         Ok(())
     }
 
-    /// Write digit D at position P
-    pub async fn display_digit(&mut self, position: u8, digit: u8) -> Result<(), Error<E>> {
-        // TO DO: include hex digits:
-        // 0x00 - 0x0f: Displays a single digit 0-9 or hexadecimal digit A-F.
+    /// Display time in HH:MM format, with an optional dot between them.
+    /// If `leading_zero` is false, hours below 10 are shown without the leading zero, e.g. ` 9.05`
+    pub async fn display_time_padded(
+        &mut self,
+        hours: u8,
+        minutes: u8,
+        dot: bool,
+        leading_zero: bool,
+    ) -> Result<(), Error<E>> {
+        if hours > 23 {
+            return Err(Error::ValueOutOfRange(hours));
+        } else if minutes > 59 {
+            return Err(Error::ValueOutOfRange(minutes));
+        }
 
-        if position > 3 || digit > 9 {
-            return Err(Error::InvalidInputData);
-        } else {
-            self.write(&[Register::POSITION_SETTING, position, digit]).await?
-        };
+        let mut chars = commands::get_digits((hours as u16) * 100 + minutes as u16);
 
-        Ok(())
+        // the leading zero is blanked before writing, so it never shows up
+        // (the dots are set separately, so this doesn't affect them)
+        if !leading_zero && hours < 10 {
+            chars[0] = b' ';
+        }
+
+        self.write_chars_with_dots(&chars, [false, dot, false, false]).await // dot at second position
     }
 
-    /// Display a number using all four digits
-    // TO DO: ADD A BOOLEAN SWITCH "with_leading_zeros"
-    pub async fn display_number(&mut self, number: u16) -> Result<(), Error<E>> {
-        if number > 9999 {
-            return Err(Error::InvalidInputData);
+    /// Display time given in 24-hour format (0-23) as 12-hour time in H.MM format, e.g. ` 1.05`,
+    /// with an optional dot between hours and minutes. The rightmost dot is on for PM.
+    pub async fn display_time_12h(&mut self, hours: u8, minutes: u8, dot: bool) -> Result<(), Error<E>> {
+        if hours > 23 {
+            return Err(Error::ValueOutOfRange(hours));
+        } else if minutes > 59 {
+            return Err(Error::ValueOutOfRange(minutes));
         }
 
-        let digits = TWIDisplay::<I2C>::get_digits(number);
+        let hours_12h = match hours % 12 {
+            0 => 12,
+            h => h,
+        };
+        let pm = hours >= 12;
 
-        for (idx, digit) in digits.iter().enumerate() {
-            self.display_digit(idx as u8, *digit).await?
+        let mut chars = commands::get_digits((hours_12h as u16) * 100 + minutes as u16);
+        if hours_12h < 10 {
+            chars[0] = b' ';
         }
 
-        Ok(())
+        self.write_chars_with_dots(&chars, [false, dot, false, pm]).await
     }
 
-    /// Send a character to the display without specifying the position
-    pub async fn send_char(&mut self, ch: char) -> Result<(), Error<E>> {
-        // TO DO: restrict to 0x0g - 0x79
+    /// Display time from an `(hours, minutes, seconds)` tuple in HH.MM format.
+    /// If `dot` is true, the dot blinks with the seconds: on for even seconds, off for odd ones.
+    pub async fn display_clock_tuple(&mut self, hms: (u8, u8, u8), dot: bool) -> Result<(), Error<E>> {
+        let (hours, minutes, seconds) = hms;
 
-        self.write(&[ch as u8]).await?;
-        Ok(())
+        if seconds > 59 {
+            return Err(Error::ValueOutOfRange(seconds));
+        }
+
+        self.display_time(hours, minutes, dot && seconds.is_multiple_of(2)).await
     }
 
-    /// Write character C at position P
-    pub async fn display_char(&mut self, position: u8, ch: char) -> Result<(), Error<E>> {
-        // TO DO: restrict to 0x0g - 0x79
+    /// Display time in HH.MM format, or in MM.SS format if `show_seconds` is true.
+    /// The dot between the two fields is always on.
+    pub async fn display_clock_toggle(
+        &mut self,
+        hours: u8,
+        minutes: u8,
+        seconds: u8,
+        show_seconds: bool,
+    ) -> Result<(), Error<E>> {
+        if hours > 23 {
+            return Err(Error::ValueOutOfRange(hours));
+        } else if minutes > 59 {
+            return Err(Error::ValueOutOfRange(minutes));
+        } else if seconds > 59 {
+            return Err(Error::ValueOutOfRange(seconds));
+        }
 
-        if position > 3 {
-            return Err(Error::InvalidInputData);
-        } else {
-            self.write(&[Register::POSITION_SETTING, position, ch as u8]).await?;
+        let time_value = match show_seconds {
+            true => (minutes as u16) * 100 + seconds as u16,
+            false => (hours as u16) * 100 + minutes as u16,
         };
-        Ok(())
+
+        // dot at second position
+        self.write_chars_with_dots(&commands::get_digits(time_value), [false, true, false, false]).await
     }
 
-    /// Send text to the display
-    pub async fn send_text(&mut self, text: &str) -> Result<(), Error<E>> {
-        for ch in text.chars() {
-            self.send_char(ch).await?
+    /// Display elapsed time in MM.SS format, e.g. for a stopwatch, with an optional dot between them.
+    /// Unlike `display_time()`, the first field goes up to 99.
+    pub async fn display_stopwatch(&mut self, minutes: u8, seconds: u8, dot: bool) -> Result<(), Error<E>> {
+        if minutes > 99 {
+            return Err(Error::ValueOutOfRange(minutes));
+        } else if seconds > 59 {
+            return Err(Error::ValueOutOfRange(seconds));
         }
-        Ok(())
-    }
 
-    /// Display time in HH:MM format, with an optional dot between them
-    pub async fn display_time(&mut self, hours: u8, minutes: u8, dot: bool) -> Result<(), Error<E>> {
-        if hours > 23 || minutes > 59 {
-            return Err(Error::InvalidInputData);
-        } else {
-            let time_value = (hours as u16) * 100 + minutes as u16;
+        let time_value = (minutes as u16) * 100 + seconds as u16;
+
+        // dot at second position
+        self.write_chars_with_dots(&commands::get_digits(time_value), [false, dot, false, false]).await
+    }
 
-            self.display_number(time_value).await?
+    /// Display the remaining time of a countdown, e.g. a kitchen timer, with an optional dot
+    /// between the two fields: in MM.SS format under an hour, e.g. `01.30` for 90 seconds,
+    /// otherwise in HH.MM format without the leading zero, e.g. ` 1.01` for 3661 seconds.
+    /// Every value fits, the longest countdown (65535 seconds) is shown as `18.12`.
+    pub async fn display_countdown(&mut self, total_seconds: u16, dot: bool) -> Result<(), Error<E>> {
+        let hours = total_seconds / 3600;
+        let minutes = (total_seconds % 3600) / 60;
+        let seconds = total_seconds % 60;
+
+        let chars = match hours {
+            0 => commands::get_digits(minutes * 100 + seconds),
+            _ => {
+                let mut chars = commands::get_digits(hours * 100 + minutes);
+                if hours < 10 {
+                    chars[0] = b' ';
+                }
+                chars
+            }
         };
 
-        match dot {
-            true => self.display_dots([false, true, false, false]).await?, // dot at second position
-            false => self.display_dots([false, false, false, false]).await?,
-        }
+        self.write_chars_with_dots(&chars, [false, dot, false, false]).await // dot at second position
+    }
 
-        Ok(())
+    // seconds and days are right-aligned at positions 0-2 with the unit at position 3, like percentages
+
+    /// Display an elapsed duration in the best fitting format:
+    /// - under a minute: the seconds and `S`, e.g. ` 45S`
+    /// - under an hour: MM.SS, e.g. `05.00` for 300 seconds
+    /// - under a day: HH.MM without the leading zero, e.g. ` 2.00` for 7200 seconds
+    /// - up to 999 days: the whole days and `d`, e.g. `  2d` for 200000 seconds
+    ///
+    /// The central dot is on in the MM.SS and HH.MM formats and off otherwise.
+    /// Durations of 1000 days and more are rejected.
+    pub async fn display_duration(&mut self, seconds: u32) -> Result<(), Error<E>> {
+        let (chars, dot) = match seconds {
+            0..60 => {
+                let digits = commands::blank_leading_zeros(commands::get_digits(seconds as u16));
+                ([digits[1], digits[2], digits[3], b'S'], false)
+            }
+            60..3600 => (commands::get_digits((seconds / 60 * 100 + seconds % 60) as u16), true),
+            3600..86400 => {
+                let hours = seconds / 3600;
+                let mut chars = commands::get_digits((hours * 100 + seconds % 3600 / 60) as u16);
+                if hours < 10 {
+                    chars[0] = b' ';
+                }
+                (chars, true)
+            }
+            86400..86_400_000 => {
+                let digits = commands::blank_leading_zeros(commands::get_digits((seconds / 86400) as u16));
+                ([digits[1], digits[2], digits[3], b'd'], false)
+            }
+            _ => return Err(Error::NumberTooLarge(seconds.into())),
+        };
+
+        self.write_chars_with_dots(&chars, [false, dot, false, false]).await // dot at second position
     }
 
     // TO DO: add display_date(month, day, format) function
@@ -468,25 +3170,15 @@ where
         format: DateFormat,
         dot: bool,
     ) -> Result<(), Error<E>> {
-        if month > 12 || month < 1 {
-            return Err(Error::InvalidInputData);
-        } else if day < 1 {
-            return Err(Error::InvalidInputData);
-        } else if (month == 1
-            || month == 3
-            || month == 5
-            || month == 7
-            || month == 8
-            || month == 10
-            || month == 12)
-            && day > 31
-        {
-            return Err(Error::InvalidInputData);
-        } else if (month == 4 || month == 6 || month == 9 || month == 11) && day > 30 {
-            return Err(Error::InvalidInputData);
-        } else if month == 2 && day > 29 {
-            // no checking for leap years
-            return Err(Error::InvalidInputData);
+        let max_day = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 => 29, // no checking for leap years
+            _ => return Err(Error::DateOutOfRange(month, day)),
+        };
+
+        if !(1..=max_day).contains(&day) {
+            return Err(Error::DateOutOfRange(month, day));
         }
 
         let date_number: u16 = match format {
@@ -494,25 +3186,49 @@ where
             DateFormat::MMDD => month as u16 * 100 + day as u16,
         };
 
-        self.display_number(date_number).await?;
+        // dot at second position
+        self.write_chars_with_dots(&commands::get_digits(date_number), [false, dot, false, false]).await
+    }
+
+    /// Display date in MMDD or DDMM format like `display_date()`, checking the day against the given year:
+    /// February 29 is rejected in non-leap years. The year itself is not displayed.
+    pub async fn display_date_checked(
+        &mut self,
+        year: u16,
+        month: u8,
+        day: u8,
+        format: DateFormat,
+        dot: bool,
+    ) -> Result<(), Error<E>> {
+        if month == 2 && day == 29 && !is_leap_year(year) {
+            return Err(Error::DateOutOfRange(month, day));
+        }
+
+        self.display_date(month, day, format, dot).await
+    }
 
-        match dot {
-            true => self.display_dots([false, true, false, false]).await?, // dot at second position
-            false => self.display_dots([false, false, false, false]).await?,
+    /// Display a year between 0 and 9999 on all four digits, with all the dots off
+    pub async fn display_year(&mut self, year: u16) -> Result<(), Error<E>> {
+        if year > 9999 {
+            return Err(Error::NumberTooLarge(year.into()));
         }
 
-        Ok(())
+        self.write_chars_with_dots(&commands::get_digits(year), [false; 4]).await
     }
 
     /// Set the display mode: Scroll or Rotate (see documentation)
     pub async fn set_mode(&mut self, mode: Mode) -> Result<(), Error<E>> {
-        match mode {
-            Mode::Rotate => self.write(&[Register::MODE_SETTING, 0]).await?,
-            Mode::Scroll => self.write(&[Register::MODE_SETTING, 1]).await?,
-        }
+        self.write(&commands::encode_mode(mode)).await?;
+        self.mode = mode;
         Ok(())
     }
 
+    /// Get the last mode set with `set_mode()`
+    /// (the default `Rotate` mode is assumed until it's called for the first time)
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
     /// Display data with units (temperature, humidity) and defined thresholds
     async fn display_data(
         &mut self,
@@ -566,8 +3282,12 @@ where
             let hundreds: u8 = (data.abs() / 100) as u8;
             let decimals: u8 = ((data.abs() % 100) / 10) as u8;
 
+            // right-aligned with no leading zeros, e.g. 5 is shown as `  5C`, 25 as ` 25C`, 100 as `100C`;
+            // the minus sign is placed right before the first digit,
+            // e.g. -5 is shown as ` -5C`, -50 as `-50C` and -99 as `-99C`
+
             // position 0 (hundreds or minus sign)
-            if data < 0 {
+            if data < 0 && decimals != 0 {
                 self.display_char(0, '-').await?
             } else if hundreds == 0 {
                 self.display_char(0, ' ').await?
@@ -575,8 +3295,10 @@ where
                 self.display_digit(0, hundreds).await?
             }
 
-            // position 1 (decimals)
-            if (hundreds == 0 || data < 0) && decimals == 0 {
+            // position 1 (decimals or minus sign)
+            if data < 0 && decimals == 0 {
+                self.display_char(1, '-').await?
+            } else if hundreds == 0 && decimals == 0 {
                 self.display_char(1, ' ').await?
                 //self.write(&[Register::POSITION_SETTING, 1, ' ' as u8])?
             } else {
@@ -593,8 +3315,8 @@ where
         Ok(())
     }
 
-    /// Display temperature between -99 and 999 with a chosen unit, with lower and upper threshold
-
+    /// Display temperature between -99 and 999 with a chosen unit, with lower and upper threshold.
+    /// The value is right-aligned before the unit, e.g. `  5C`, ` 25C`, `100C` or ` -5C`.
     pub async fn display_temperature(
         &mut self,
         temperature: i16,
@@ -607,35 +3329,513 @@ where
             TempUnits::Fahrenheit => 'F',
         };
 
-        self.display_data(temperature, temp_unit, lo_thresh, hi_thresh, -99, 999).await?;
+        self.composite(async |display| {
+            display.display_data(temperature, temp_unit, lo_thresh, hi_thresh, -99, 999).await
+        })
+        .await
+    }
+
+    /// Display temperature like `display_temperature()`, choosing what is shown for a temperature
+    /// below the lower or above the upper threshold (or outside the -99 to 999 range):
+    /// the `-LL-`, `-HH-` or `----` marker, the temperature clamped to the threshold (or range),
+    /// e.g. 250 is shown as ` 60C` with an upper threshold at 60, or a blank display.
+    /// Blanking takes one transaction for the positions and one turning off the dots.
+    pub async fn display_temperature_clamped(
+        &mut self,
+        temperature: i16,
+        unit: TempUnits,
+        lo_thresh: Option<i16>,
+        hi_thresh: Option<i16>,
+        behavior: ClampBehavior,
+    ) -> Result<(), Error<E>> {
+        let lo_th = lo_thresh.unwrap_or(-99).max(-99);
+        let hi_th = hi_thresh.unwrap_or(999).min(999);
+        let in_range = (lo_th..=hi_th).contains(&temperature);
+
+        match behavior {
+            ClampBehavior::ShowMarker => self.display_temperature(temperature, unit, lo_thresh, hi_thresh).await,
+            ClampBehavior::Clamp => {
+                let clamped = temperature.max(lo_th).min(hi_th);
+                self.display_temperature(clamped, unit, None, None).await
+            }
+            ClampBehavior::Blank if in_range => self.display_temperature(temperature, unit, None, None).await,
+            ClampBehavior::Blank => self.write_chars_with_dots(&[b' '; 4], [false; 4]).await,
+        }
+    }
+
+    /// Display temperature like `display_temperature()` with no thresholds, and its trend on the dots:
+    /// the rightmost dot for `Rising`, the leftmost one for `Falling` and none for `Steady`.
+    /// The other dots are turned off.
+    pub async fn display_temperature_with_trend(
+        &mut self,
+        temperature: i16,
+        unit: TempUnits,
+        trend: Trend,
+    ) -> Result<(), Error<E>> {
+        let dots = match trend {
+            Trend::Rising => [false, false, false, true],
+            Trend::Falling => [true, false, false, false],
+            Trend::Steady => [false; 4],
+        };
+
+        self.composite(async |display| {
+            display.display_temperature(temperature, unit, None, None).await?;
+            display.display_dots(dots).await
+        })
+        .await
+    }
+
+    /// Display temperature like `display_temperature()`, with hysteresis on the thresholds
+    /// to avoid flickering when the temperature hovers around them. Once above the upper threshold
+    /// (`-HH-`), the temperature must drop below `hi_thresh - hysteresis` to be shown again,
+    /// and once below the lower threshold (`-LL-`), it must rise above `lo_thresh + hysteresis`.
+    /// The driver keeps track of the last state, so use it for a single temperature reading.
+    pub async fn display_temperature_hyst(
+        &mut self,
+        temperature: i16,
+        unit: TempUnits,
+        lo_thresh: Option<i16>,
+        hi_thresh: Option<i16>,
+        hysteresis: u8,
+    ) -> Result<(), Error<E>> {
+        let hysteresis = hysteresis as i16;
+
+        // widen the threshold that was crossed last, so that the state holds within the hysteresis
+        let lo_thresh = lo_thresh.map(|lo| match self.threshold_state {
+            ThresholdState::Low => lo.saturating_add(hysteresis + 1),
+            _ => lo,
+        });
+        let hi_thresh = hi_thresh.map(|hi| match self.threshold_state {
+            ThresholdState::High => hi.saturating_sub(hysteresis + 1),
+            _ => hi,
+        });
+
+        // same order of the checks as in display_data
+        let state = if lo_thresh.is_some_and(|lo| temperature < lo) {
+            ThresholdState::Low
+        } else if hi_thresh.is_some_and(|hi| temperature > hi) {
+            ThresholdState::High
+        } else {
+            ThresholdState::Normal
+        };
+
+        self.display_temperature(temperature, unit, lo_thresh, hi_thresh).await?;
+        self.threshold_state = state;
+        Ok(())
+    }
+
+    /// Display temperature given in Celsius degrees in a chosen unit, converting it to Fahrenheit
+    /// if needed (with integer math, rounded towards zero). The thresholds are given in the
+    /// displayed unit, and work as in `display_temperature()`.
+    pub async fn display_temperature_converted(
+        &mut self,
+        celsius: i16,
+        display_as: TempUnits,
+        lo_thresh: Option<i16>,
+        hi_thresh: Option<i16>,
+    ) -> Result<(), Error<E>> {
+        let temperature = match display_as {
+            TempUnits::Celsius => celsius,
+            // values out of the i16 range are out of the display limits anyway
+            TempUnits::Fahrenheit => (celsius as i32 * 9 / 5 + 32).clamp(i16::MIN as i32, i16::MAX as i32) as i16,
+        };
+
+        self.display_temperature(temperature, display_as, lo_thresh, hi_thresh).await
+    }
+
+    /// Display temperature given in tenths of a degree (-99 to 999, i.e. -9.9 to 99.9 degrees)
+    /// with one decimal and a chosen unit. The value takes positions 0-2 with the dot after
+    /// position 1, and the unit is shown at position 3, e.g. `23.4C` for 234 or `-0.5C` for -5.
+    pub async fn display_temperature_decimal(&mut self, tenths: i16, unit: TempUnits) -> Result<(), Error<E>> {
+        if !(-99..=999).contains(&tenths) {
+            return Err(Error::NumberTooLarge(tenths.into()));
+        }
+
+        let temp_unit = match unit {
+            TempUnits::Celsius => 'C',
+            TempUnits::Fahrenheit => 'F',
+        };
+
+        let magnitude = tenths.unsigned_abs();
+        let tens = (magnitude / 100) as u8;
+
+        let mut chars = [b' ', ((magnitude / 10) % 10) as u8, (magnitude % 10) as u8, temp_unit as u8];
+        // magnitude is at most 99 for negative values, so the first position is free
+        if tenths < 0 {
+            chars[0] = b'-';
+        } else if tens > 0 {
+            chars[0] = tens;
+        }
+
+        self.write_chars_with_dots(&chars, [false, true, false, false]).await // dot at second position
+    }
+
+    /// Display a temperature range: the low value, the ` -- ` separator and the high value,
+    /// each for `step_ms` milliseconds. Both values must be between -99 and 999.
+    /// This shows the range once, call it in a loop to keep cycling.
+    pub async fn scroll_range<D: DelayNs>(
+        &mut self,
+        low: i16,
+        high: i16,
+        unit: TempUnits,
+        delay: &mut D,
+        step_ms: u32,
+    ) -> Result<(), Error<E>> {
+        if let Some(&value) = [low, high].iter().find(|value| !(-99..=999).contains(*value)) {
+            return Err(Error::NumberTooLarge(value.into()));
+        } else if low > high {
+            return Err(Error::InvalidInputData);
+        }
+
+        self.display_temperature(low, unit, None, None).await?;
+        delay.delay_ms(step_ms).await;
+
+        self.redraw_text(" -- ").await?;
+        delay.delay_ms(step_ms).await;
+
+        self.display_temperature(high, unit, None, None).await?;
+        delay.delay_ms(step_ms).await;
+
+        Ok(())
+    }
+
+    /// Display a value between -99 and 999 followed by a unit character, e.g. ` 12A`,
+    /// with lower and upper threshold working as in `display_temperature()`.
+    /// Units that can't be displayed (see `is_displayable()`) are rejected.
+    pub async fn display_value_with_unit(
+        &mut self,
+        value: i16,
+        unit: char,
+        lo_thresh: Option<i16>,
+        hi_thresh: Option<i16>,
+    ) -> Result<(), Error<E>> {
+        if !is_displayable(unit) {
+            return Err(Error::UnsupportedChar(unit));
+        }
+
+        self.composite(async |display| {
+            display.display_data(value, unit, lo_thresh, hi_thresh, -99, 999).await
+        })
+        .await
+    }
+
+    /// Display a value between 0 and 99 at positions 0-1 followed by a two-character unit, e.g. `42db` for decibels.
+    /// Values below 10 are shown without the leading zero. Larger values and unit characters
+    /// that can't be displayed (see `is_displayable()`) are rejected, e.g. the `z` of `Hz`.
+    /// Units are legible with the characters in `CHAR_SEGMENTS`, e.g. `db` or `Pa`.
+    pub async fn display_value_unit2(&mut self, value: u8, unit: [char; 2]) -> Result<(), Error<E>> {
+        if value > 99 {
+            return Err(Error::NumberTooLarge(value.into()));
+        } else if let Some(&ch) = unit.iter().find(|&&ch| !is_displayable(ch)) {
+            return Err(Error::UnsupportedChar(ch));
+        }
+
+        let tens = match value / 10 {
+            0 => b' ',
+            t => t,
+        };
+
+        self.write_chars(&[tens, value % 10, unit[0] as u8, unit[1] as u8]).await?;
+        Ok(())
+    }
+
+    // there is no '%' glyph, the percentage is followed by 'P' instead
+
+    /// Display a percentage (0-100) right-aligned at positions 0-2, without leading zeros,
+    /// followed by `P` at position 3, e.g. ` 85P`
+    pub async fn display_percentage(&mut self, percent: u8) -> Result<(), Error<E>> {
+        if percent > 100 {
+            return Err(Error::ValueOutOfRange(percent));
+        }
+
+        let digits = commands::get_digits(percent as u16);
+        // the first digit is always 0 here, the other three are shifted left to make room for 'P'
+        let chars = commands::blank_leading_zeros(digits);
 
+        self.write_chars(&[chars[1], chars[2], chars[3], b'P']).await?;
         Ok(())
     }
 
-    /// Display humidity in range 0-100, with lower and upper threshold.
+    /// Display a value between 0 and 255, e.g. an octet of an IP address, right-aligned
+    /// at positions 0-2 without leading zeros, followed by the dot at position 2, e.g. `192.` or `  5.`.
+    /// Position 3 is left blank and the other dots are turned off.
+    pub async fn display_octet(&mut self, value: u8) -> Result<(), Error<E>> {
+        let digits = commands::get_digits(value as u16);
+        // an octet has at most three digits, so they end at position 2, where the dot
+        // separates it from the next octet, and position 3 stays free
+        let chars = commands::blank_leading_zeros(digits);
+
+        self.write_chars_with_dots(&[chars[1], chars[2], chars[3], b' '], [false, false, true, false]).await
+    }
+
+    // the battery level is shown as 'b' followed by a bar of three cells,
+    // each filled cell has the top, middle and bottom segments on:
+    // 0%: 'b' only, 1-33%: one cell, 34-66%: two cells, 67-100%: three cells
+
+    /// Display the battery level (0-100%, higher values are treated as 100%) as a three-cell bar
+    pub async fn display_battery(&mut self, percent: u8) -> Result<(), Error<E>> {
+        const LETTER_B: u8 = SegmentMask::SEG_C
+            | SegmentMask::SEG_D
+            | SegmentMask::SEG_E
+            | SegmentMask::SEG_F
+            | SegmentMask::SEG_G;
+        const FULL_CELL: u8 = SegmentMask::SEG_A | SegmentMask::SEG_D | SegmentMask::SEG_G;
+
+        let percent = percent.min(100);
+        let cells = (percent as u16 * 3).div_ceil(100) as usize;
+
+        let mut segments = [LETTER_B, 0, 0, 0];
+        for cell in segments.iter_mut().skip(1).take(cells) {
+            *cell = FULL_CELL;
+        }
+
+        self.write_frame(&segments).await?;
+        Ok(())
+    }
 
+    // the bar uses the bottom segment of each position, filled from the left:
+    // 0%: empty, 1-25%: one position, 26-50%: two positions and so on
+
+    /// Display a level (0-100%) as a horizontal bar, e.g. for level meters or progress
+    pub async fn display_bar(&mut self, percent: u8) -> Result<(), Error<E>> {
+        if percent > 100 {
+            return Err(Error::ValueOutOfRange(percent));
+        }
+
+        let filled = (percent as u16 * 4).div_ceil(100) as usize;
+
+        let mut segments = [0u8; 4];
+        for position in segments.iter_mut().take(filled) {
+            *position = SegmentMask::SEG_D;
+        }
+
+        self.write_frame(&segments).await?;
+        Ok(())
+    }
+
+    /// Show frame 0-5 of a busy spinner at the given position: a single outer segment
+    /// going clockwise from the top (A, B, C, D, E, F). Advance `frame` on a timer to animate it.
+    pub async fn spinner_frame(&mut self, position: impl Into<Position>, frame: u8) -> Result<(), Error<E>> {
+        if frame > 5 {
+            return Err(Error::ValueOutOfRange(frame));
+        }
+
+        // segments A-F are bits 0-5, in clockwise order
+        self.define_custom_char(position, 1 << frame).await
+    }
+
+    /// Display humidity in range 0-100, with lower and upper threshold.
     pub async fn display_humidity(
         &mut self,
         humidity: i16,
         lo_thresh: Option<i16>,
         hi_thresh: Option<i16>,
     ) -> Result<(), Error<E>> {
-        self.display_data(humidity, 'H', lo_thresh, hi_thresh, 0, 100).await?;
+        self.composite(async |display| {
+            display.display_data(humidity, 'H', lo_thresh, hi_thresh, 0, 100).await
+        })
+        .await
+    }
+
+    /// Run a demo of the main functions of the driver, taking about 20 seconds.
+    /// The display is left cleared, with dots off and in the default Rotate mode.
+    pub async fn demo<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        // all segments and dots on
+        self.write_frame(&[0xff; 4]).await?;
+        self.display_dots([true, true, true, true]).await?;
+        delay.delay_ms(1000).await;
+        self.display_dots([false, false, false, false]).await?;
+
+        // counting
+        for number in 0..=50 {
+            self.display_number(number).await?;
+            delay.delay_ms(50).await;
+        }
+        delay.delay_ms(1000).await;
+
+        // scrolling text
+        self.clear_display_confirmed(delay).await?;
+        self.set_mode(Mode::Scroll).await?;
+        for ch in "HELLO    ".chars() {
+            self.send_char(ch).await?;
+            delay.delay_ms(300).await;
+        }
+        self.set_mode(Mode::Rotate).await?;
+
+        // clock with a blinking dot
+        for seconds in 0..6 {
+            self.display_time(12, 34, seconds % 2 == 0).await?;
+            delay.delay_ms(500).await;
+        }
+        self.display_clock_toggle(12, 34, 56, true).await?;
+        delay.delay_ms(1500).await;
+        self.display_dots([false, false, false, false]).await?;
+
+        // sensor readings
+        self.display_temperature(21, TempUnits::Celsius, None, None).await?;
+        delay.delay_ms(1500).await;
+        self.display_temperature(-7, TempUnits::Celsius, None, None).await?;
+        delay.delay_ms(1500).await;
+        self.display_humidity(45, None, None).await?;
+        delay.delay_ms(1500).await;
+
+        // battery levels
+        for percent in [0, 33, 66, 100] {
+            self.display_battery(percent).await?;
+            delay.delay_ms(500).await;
+        }
+
+        // brightness sweep
+        for brightness in [255, 127, 32, 127, 255] {
+            self.set_brightness(brightness).await?;
+            self.display_number(8888).await?;
+            delay.delay_ms(300).await;
+        }
+
+        self.clear_display().await?;
+        Ok(())
+    }
+
+    /// Run a self-test for bring-up, e.g. to find dead segments or wiring faults, taking about 4 seconds:
+    /// all the segments, including the decimal points, are lit on all the positions with raw segment
+    /// writes, then all the dots and the colon, then the brightness is swept down and up again.
+    /// The display is left cleared, with dots off and at the last brightness set.
+    pub async fn self_test<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        let brightness = self.brightness;
+
+        // all segments on all positions, in one transaction
+        let mut frame: Frame = [None; MAX_DIGITS as usize];
+        for cell in frame.iter_mut().take(self.digits as usize) {
+            *cell = Some(Cell::Segments(0xff));
+        }
+        self.write_known_positions(frame).await?;
+        delay.delay_ms(1000).await;
+
+        // all dots and the colon
+        self.display_dots([true, true, true, true]).await?;
+        self.set_colon(true).await?;
+        delay.delay_ms(1000).await;
+
+        // brightness sweep
+        self.fade_brightness(255, 0, 8, 100, delay).await?;
+        self.fade_brightness(0, 255, 8, 100, delay).await?;
+
+        self.clear_display().await?;
+        self.write_dots(0).await?;
+        self.set_brightness(brightness).await
+    }
+}
+
+/// Helper dimming the display after a period of inactivity.
+///
+/// Timing is left to the application: it reports the elapsed time with `tick()`
+/// and content updates with `activity()`.
+#[derive(Debug)]
+pub struct IdleDimmer {
+    timeout_ms: u32,
+    dim_brightness: u8,
+    idle_ms: u32,
+    dimmed: bool,
+}
+
+impl IdleDimmer {
+    /// Create a new dimmer, lowering the brightness to `dim_brightness` after `timeout_ms` of inactivity
+    pub fn new(timeout_ms: u32, dim_brightness: u8) -> Self {
+        IdleDimmer {
+            timeout_ms,
+            dim_brightness,
+            idle_ms: 0,
+            dimmed: false,
+        }
+    }
+
+    /// Add `elapsed_ms` to the idle time and dim the display once the timeout is reached
+    pub async fn tick<I2C, E>(
+        &mut self,
+        display: &mut TWIDisplay<I2C>,
+        elapsed_ms: u32,
+    ) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        self.idle_ms = self.idle_ms.saturating_add(elapsed_ms);
+
+        if !self.dimmed && self.idle_ms >= self.timeout_ms {
+            display.write_brightness(self.dim_brightness).await?;
+            self.dimmed = true;
+        }
+        Ok(())
+    }
 
+    /// Reset the idle time after a content update, restoring the brightness if the display was dimmed
+    pub async fn activity<I2C, E>(&mut self, display: &mut TWIDisplay<I2C>) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        self.idle_ms = 0;
+
+        if self.dimmed {
+            display.write_brightness(display.brightness()).await?;
+            self.dimmed = false;
+        }
         Ok(())
     }
 
-    /// Helper function to get digits from a 4-digit number
-    fn get_digits(number: u16) -> [u8; 4] {
-        let mut data = number;
-        let mut digits = [0u8; 4];
-        digits[0] = (data / 1000) as u8;
-        data = data % 1000;
-        digits[1] = (data / 100) as u8;
-        data = data % 100;
-        digits[2] = (data / 10) as u8;
-        data = data % 10;
-        digits[3] = data as u8;
-        digits
+    /// Check if the display is currently dimmed
+    pub fn is_dimmed(&self) -> bool {
+        self.dimmed
+    }
+}
+
+/// Adapter for writing to the display with `write!()`, through `core::fmt::Write`.
+///
+/// Since `core::fmt::Write` is synchronous, the written characters are only collected
+/// in a four-character buffer, and pushed to the display with `flush()`.
+/// Writing more than four characters, or characters that can't be displayed, fails with `core::fmt::Error`.
+#[derive(Debug)]
+pub struct DisplayWriter {
+    chars: [u8; 4],
+    len: usize,
+}
+
+impl DisplayWriter {
+    /// Create a new writer with an empty buffer
+    pub fn new() -> Self {
+        DisplayWriter {
+            chars: [b' '; 4],
+            len: 0,
+        }
+    }
+
+    /// Write the buffered characters to the display in a single I2C transaction, left-aligned
+    /// and padded with spaces, then empty the buffer for the next update
+    pub async fn flush<I2C, E>(&mut self, display: &mut TWIDisplay<I2C>) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        let mut chars = [b' '; 4];
+        chars[..self.len].copy_from_slice(&self.chars[..self.len]);
+
+        self.len = 0;
+        display.write_chars(&chars).await
+    }
+}
+
+impl Default for DisplayWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Write for DisplayWriter {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for ch in s.chars() {
+            if self.len == 4 || !is_displayable(ch) {
+                return Err(core::fmt::Error);
+            }
+            self.chars[self.len] = ch as u8;
+            self.len += 1;
+        }
+        Ok(())
     }
 }
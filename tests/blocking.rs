@@ -0,0 +1,122 @@
+//! Blocking driver, only built with the `blocking` feature
+
+#![cfg(feature = "blocking")]
+
+use akafugu_twidisplay_async::*;
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::{ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
+
+/// Blocking I2C bus recording each write, NACKing all of them if `nack` is set
+#[derive(Debug, Default)]
+struct Mock {
+    writes: Vec<Vec<u8>>,
+    nack: bool,
+}
+
+impl ErrorType for Mock {
+    type Error = ErrorKind;
+}
+
+impl I2c for Mock {
+    fn transaction(&mut self, _address: u8, operations: &mut [Operation<'_>]) -> Result<(), Self::Error> {
+        for operation in operations {
+            match operation {
+                Operation::Write(_) if self.nack => return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+                Operation::Write(bytes) => self.writes.push(bytes.to_vec()),
+                Operation::Read(buffer) => buffer.fill(1),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Blocking delay recording each wait, in milliseconds
+#[derive(Debug, Default)]
+struct Delay(Vec<u32>);
+
+impl DelayNs for Delay {
+    fn delay_ns(&mut self, ns: u32) {
+        self.0.push(ns / 1_000_000);
+    }
+}
+
+fn display() -> TWIDisplayBlocking<Mock> {
+    TWIDisplayBlocking::new(Mock::default(), DEFAULT_ADDRESS)
+}
+
+#[test]
+fn sends_the_same_bytes_as_the_async_driver() {
+    let mut d = display();
+
+    d.display_number(1234).unwrap();
+    d.display_time(12, 34, true).unwrap();
+    assert!(matches!(d.display_digit(4, 1), Err(Error::PositionOutOfRange(4))));
+
+    assert_eq!(
+        d.i2c_ref().writes,
+        [
+            vec![0x89, 0, 1, 0x89, 1, 2, 0x89, 2, 3, 0x89, 3, 4],
+            vec![0x89, 0, 1, 0x89, 1, 2, 0x89, 2, 3, 0x89, 3, 4],
+            vec![0x85, 0b0000_0100],
+        ]
+    );
+}
+
+#[test]
+fn waits_on_the_blocking_delay() {
+    let mut d = display();
+    let mut delay = Delay::default();
+
+    d.blink(2, 300, 100, &mut delay).unwrap();
+
+    assert_eq!(d.i2c_ref().writes, [[0x80, 0], [0x80, 255]].repeat(2));
+    assert_eq!(delay.0, [100, 300, 100, 300]);
+}
+
+#[test]
+fn idle_dimmer_and_writer_take_the_blocking_driver() {
+    use core::fmt::Write;
+
+    let mut d = display();
+    let mut dimmer = IdleDimmer::new(1000, 16);
+    let mut writer = DisplayWriter::new();
+
+    dimmer.tick_blocking(&mut d, 1000).unwrap();
+    dimmer.activity_blocking(&mut d).unwrap();
+    write!(writer, "AB").unwrap();
+    writer.flush_blocking(&mut d).unwrap();
+
+    assert_eq!(
+        d.i2c_ref().writes,
+        [vec![0x80, 16], vec![0x80, 255], vec![0x89, 0, b'A', 0x89, 1, b'B', 0x89, 2, b' ', 0x89, 3, b' ']]
+    );
+}
+
+#[test]
+fn shares_the_bus_through_embedded_hal_bus() {
+    use core::cell::RefCell;
+    use embedded_hal_bus::i2c::RefCellDevice;
+
+    let bus = RefCell::new(Mock::default());
+    let mut d = TWIDisplayBlocking::new(RefCellDevice::new(&bus), DEFAULT_ADDRESS);
+    let mut other = RefCellDevice::new(&bus);
+
+    d.display_number(1234).unwrap();
+    other.write(0x40, &[0x01]).unwrap();
+    d.clear_display().unwrap();
+
+    assert_eq!(bus.borrow().writes.len(), 3);
+    assert_eq!(bus.borrow().writes[2], [0x82]);
+}
+
+#[test]
+fn try_new_gives_the_bus_back_on_a_nack() {
+    let result = TWIDisplayBlocking::try_new(Mock { nack: true, ..Mock::default() }, DEFAULT_ADDRESS);
+
+    let Err((error, mut i2c)) = result else { panic!("the probe should fail") };
+    assert!(matches!(error, Error::I2C(ErrorKind::NoAcknowledge(_))));
+
+    i2c.nack = false;
+    let d = TWIDisplayBlocking::try_new(i2c, DEFAULT_ADDRESS).unwrap();
+    assert_eq!(d.destroy().writes, [vec![0x8a]]);
+}
@@ -0,0 +1,38 @@
+//! Bus ownership, device probing and register reads
+
+mod common;
+
+use akafugu_twidisplay_async::*;
+use common::*;
+
+#[test]
+fn display_ref_borrows_the_bus() {
+    let mut bus = Mock::default();
+
+    {
+        let mut d: TWIDisplayRef<'_, Mock> = TWIDisplay::new(&mut bus, DEFAULT_ADDRESS);
+        block_on(d.display_number(1234)).unwrap();
+    }
+
+    // the bus is available again once the driver is dropped
+    assert_eq!(bus.writes, [vec![0x89, 0, 1, 0x89, 1, 2, 0x89, 2, 3, 0x89, 3, 4]]);
+    assert_eq!(bus.addresses, [DEFAULT_ADDRESS]);
+}
+
+#[test]
+fn bus_accessors_keep_the_driver_state() {
+    let mut d = display();
+    block_on(d.set_brightness(80)).unwrap();
+    block_on(d.display_number(1234)).unwrap();
+
+    // e.g. reconfigure the peripheral, here clear the recorded writes
+    d.i2c_mut().writes.clear();
+    assert!(d.i2c_ref().writes.is_empty());
+
+    block_on(d.restore()).unwrap();
+    assert_eq!(d.brightness(), 80);
+    assert_eq!(screen(&d), "1234");
+
+    let bus: Mock = d.destroy();
+    assert_eq!(bus.writes.len(), 3);
+}
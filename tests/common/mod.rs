@@ -0,0 +1,140 @@
+//! Mock I2C bus and delay recording the driver's traffic, shared by the integration tests
+
+#![allow(dead_code)]
+
+use core::future::Future;
+use core::pin::pin;
+use core::task::{Context, Poll, Waker};
+
+use akafugu_twidisplay_async::{TWIDisplay, CHAR_SEGMENTS, DEFAULT_ADDRESS};
+use embedded_hal_async::delay::DelayNs;
+use embedded_hal_async::i2c::{ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
+
+/// Run a future to completion, the mocks never return `Poll::Pending`
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut future = pin!(future);
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+/// I2C bus recording each write, answering reads from `reads`
+#[derive(Debug, Default)]
+pub struct Mock {
+    /// Bytes of each write, in order
+    pub writes: Vec<Vec<u8>>,
+    /// Address of each write, in order
+    pub addresses: Vec<u8>,
+    /// Bytes returned by the next reads
+    pub reads: Vec<u8>,
+    /// Index of the write to NACK (counting all writes, also the failed ones), `None` to never fail
+    pub fail_at: Option<usize>,
+    attempts: usize,
+}
+
+impl Mock {
+    /// Bus answering reads with the given bytes
+    pub fn with_reads(reads: &[u8]) -> Self {
+        Mock { reads: reads.to_vec(), ..Default::default() }
+    }
+
+    /// Bus failing the write with the given index
+    pub fn failing_at(index: usize) -> Self {
+        Mock { fail_at: Some(index), ..Default::default() }
+    }
+}
+
+impl ErrorType for Mock {
+    type Error = ErrorKind;
+}
+
+impl I2c for Mock {
+    async fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Self::Error> {
+        for operation in operations {
+            match operation {
+                Operation::Write(bytes) => {
+                    self.attempts += 1;
+                    if self.fail_at == Some(self.attempts - 1) {
+                        return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+                    }
+                    self.writes.push(bytes.to_vec());
+                    self.addresses.push(address);
+                }
+                Operation::Read(buffer) => {
+                    for byte in buffer.iter_mut() {
+                        *byte = self.reads.remove(0);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Delay recording each wait, in milliseconds
+#[derive(Debug, Default)]
+pub struct Delay(pub Vec<u32>);
+
+impl DelayNs for Delay {
+    async fn delay_ns(&mut self, ns: u32) {
+        self.0.push(ns / 1_000_000);
+    }
+}
+
+/// Driver on a fresh mock bus at the default address
+pub fn display() -> TWIDisplay<Mock> {
+    TWIDisplay::new(Mock::default(), DEFAULT_ADDRESS)
+}
+
+/// Remove and return the writes recorded so far
+pub fn take_writes(display: &mut TWIDisplay<Mock>) -> Vec<Vec<u8>> {
+    core::mem::take(&mut display.i2c_mut().writes)
+}
+
+/// Replay the position writes (0x89 and 0x84) and clears (0x82) on a blank
+/// display of the given width and return what it shows: digit values as `0`-`F`,
+/// raw segment masks as the character from `CHAR_SEGMENTS` or `?`
+pub fn screen_of(writes: &[Vec<u8>], digits: usize) -> String {
+    let mut cells = vec![' '; digits];
+
+    for write in writes {
+        match write.first() {
+            Some(0x82) => cells.fill(' '),
+            Some(0x89) | Some(0x84) => {
+                for command in write.chunks(3) {
+                    let (position, value) = (command[1] as usize, command[2]);
+                    cells[position] = match command[0] {
+                        0x89 if value < 0x10 => char::from_digit(value.into(), 16).unwrap().to_ascii_uppercase(),
+                        0x89 => value as char,
+                        _ => CHAR_SEGMENTS
+                            .iter()
+                            .find(|(_, mask)| *mask == value & 0x7f)
+                            .map_or('?', |(ch, _)| *ch),
+                    };
+                }
+            }
+            _ => (),
+        }
+    }
+    cells.into_iter().collect()
+}
+
+/// What a 4-digit display shows after the writes recorded so far
+pub fn screen(display: &TWIDisplay<Mock>) -> String {
+    screen_of(&display.i2c_ref().writes, display.digits() as usize)
+}
+
+/// Last value written to the dots register, 0 if none
+pub fn dots(display: &TWIDisplay<Mock>) -> u8 {
+    display
+        .i2c_ref()
+        .writes
+        .iter()
+        .rev()
+        .find(|write| write[0] == 0x85)
+        .map_or(0, |write| write[1])
+}
@@ -0,0 +1,328 @@
+//! Control functions, driver state and bus load
+
+mod common;
+
+use akafugu_twidisplay_async::*;
+use common::*;
+
+/// Number of writes issued by an operation on a fresh driver
+fn writes_of(run: impl FnOnce(&mut TWIDisplay<Mock>, &mut Delay)) -> usize {
+    let mut d = display();
+    run(&mut d, &mut Delay::default());
+    d.i2c_ref().writes.len()
+}
+
+#[test]
+fn transaction_count_matches_the_writes() {
+    let cases: [(Operation, usize); 12] = [
+        (Operation::DisplayNumber, writes_of(|d, _| block_on(d.display_number(1234)).unwrap())),
+        (Operation::DisplayTime, writes_of(|d, _| block_on(d.display_time(12, 34, true)).unwrap())),
+        (
+            Operation::DisplayTemperature,
+            writes_of(|d, _| block_on(d.display_temperature(21, TempUnits::Celsius, None, None)).unwrap()),
+        ),
+        (Operation::SendText(5), writes_of(|d, _| block_on(d.send_text("HELLO")).unwrap())),
+        (Operation::ScrollText(6), writes_of(|d, delay| block_on(d.scroll_text("ABCDEF", delay, 10)).unwrap())),
+        (Operation::Init(true), writes_of(|d, _| block_on(d.init(Config::default())).unwrap())),
+        (Operation::Reset, writes_of(|d, _| block_on(d.reset()).unwrap())),
+        (Operation::Blink(3), writes_of(|d, delay| block_on(d.blink(3, 10, 10, delay)).unwrap())),
+        (Operation::Redraw, writes_of(|d, _| {
+            block_on(d.display_number(1234)).unwrap();
+            take_writes(d);
+            block_on(d.redraw()).unwrap();
+        })),
+        (Operation::Demo, writes_of(|d, delay| block_on(d.demo(delay)).unwrap())),
+        (Operation::SelfTest, writes_of(|d, delay| block_on(d.self_test(delay)).unwrap())),
+        (Operation::DimmerTick, writes_of(|d, _| block_on(IdleDimmer::new(100, 16).tick(d, 100)).unwrap())),
+    ];
+
+    for (op, writes) in cases {
+        assert_eq!(transaction_count(op), writes, "{:?}", op);
+    }
+}
+
+#[test]
+fn demo_runs_and_leaves_the_display_cleared() {
+    let mut d = display();
+    let mut delay = Delay::default();
+
+    block_on(d.demo(&mut delay)).unwrap();
+
+    let writes = &d.i2c_ref().writes;
+    assert_eq!(writes.last().unwrap(), &[0x82]);
+    assert_eq!(dots(&d), 0);
+    assert_eq!(d.mode(), Mode::Rotate);
+    assert_eq!(d.brightness(), 255);
+
+    let total_ms: u32 = delay.0.iter().sum();
+    assert!((15_000..=25_000).contains(&total_ms), "{} ms", total_ms);
+}
+
+#[test]
+fn demo_stops_at_the_first_bus_error() {
+    let mut d = TWIDisplay::new(Mock::failing_at(3), DEFAULT_ADDRESS);
+
+    assert!(matches!(block_on(d.demo(&mut Delay::default())), Err(Error::I2C(_))));
+    assert_eq!(d.i2c_ref().writes.len(), 3);
+}
+
+#[test]
+fn idle_dimmer_dims_after_the_timeout() {
+    let mut d = display();
+    let mut dimmer = IdleDimmer::new(1000, 16);
+
+    block_on(dimmer.tick(&mut d, 600)).unwrap();
+    assert!(!dimmer.is_dimmed());
+    assert!(d.i2c_ref().writes.is_empty());
+
+    block_on(dimmer.tick(&mut d, 400)).unwrap();
+    assert!(dimmer.is_dimmed());
+    assert_eq!(d.i2c_ref().writes, [vec![0x80, 16]]);
+
+    // already dimmed, nothing more is sent
+    block_on(dimmer.tick(&mut d, 5000)).unwrap();
+    assert_eq!(d.i2c_ref().writes.len(), 1);
+}
+
+#[test]
+fn idle_dimmer_restores_the_cached_brightness_on_activity() {
+    let mut d = display();
+    block_on(d.set_brightness(200)).unwrap();
+    take_writes(&mut d);
+    let mut dimmer = IdleDimmer::new(1000, 16);
+
+    // activity before the timeout only resets the idle time
+    block_on(dimmer.tick(&mut d, 900)).unwrap();
+    block_on(dimmer.activity(&mut d)).unwrap();
+    block_on(dimmer.tick(&mut d, 900)).unwrap();
+    assert!(d.i2c_ref().writes.is_empty());
+
+    block_on(dimmer.tick(&mut d, 100)).unwrap();
+    block_on(dimmer.activity(&mut d)).unwrap();
+
+    assert!(!dimmer.is_dimmed());
+    assert_eq!(d.i2c_ref().writes, [vec![0x80, 16], vec![0x80, 200]]);
+    assert_eq!(d.brightness(), 200);
+}
+
+#[test]
+fn rollback_redraws_the_previous_content_after_a_bus_error() {
+    let mut d = display();
+    block_on(d.display_time(12, 34, true)).unwrap();
+    d.set_rollback_on_error(true);
+
+    // the digits go through, the dots (third write) fail
+    d.i2c_mut().fail_at = Some(3);
+    assert!(matches!(block_on(d.display_time(7, 5, false)), Err(Error::I2C(_))));
+
+    assert_eq!(screen(&d), "1234");
+    assert_eq!(dots(&d), 0b0000_0100);
+}
+
+#[test]
+fn no_rollback_leaves_the_partial_update_by_default() {
+    let mut d = display();
+    block_on(d.display_time(12, 34, true)).unwrap();
+
+    d.i2c_mut().fail_at = Some(3);
+    assert!(matches!(block_on(d.display_time(7, 5, false)), Err(Error::I2C(_))));
+
+    assert_eq!(screen(&d), "0705");
+    assert_eq!(dots(&d), 0b0000_0100);
+    assert_eq!(d.i2c_ref().writes.len(), 3);
+}
+
+#[test]
+fn brightness_flash_restores_the_cached_brightness() {
+    let mut d = display();
+    let mut delay = Delay::default();
+    block_on(d.set_brightness(90)).unwrap();
+    take_writes(&mut d);
+
+    block_on(d.brightness_flash(255, 300, &mut delay)).unwrap();
+
+    assert_eq!(d.i2c_ref().writes, [vec![0x80, 255], vec![0x80, 90]]);
+    assert_eq!(delay.0, [300]);
+    assert_eq!(d.brightness(), 90);
+}
+
+#[test]
+fn fade_brightness_steps_linearly() {
+    let mut d = display();
+    let mut delay = Delay::default();
+
+    block_on(d.fade_brightness(0, 100, 4, 50, &mut delay)).unwrap();
+
+    let values: Vec<u8> = d.i2c_ref().writes.iter().map(|write| write[1]).collect();
+    assert_eq!(values, [0, 25, 50, 75, 100]);
+    assert_eq!(delay.0, [50; 4]);
+    assert_eq!(d.brightness(), 100);
+}
+
+#[test]
+fn fade_brightness_jumps_without_steps() {
+    for (from, to, steps) in [(80, 80, 4), (0, 100, 0)] {
+        let mut d = display();
+        let mut delay = Delay::default();
+
+        block_on(d.fade_brightness(from, to, steps, 50, &mut delay)).unwrap();
+
+        assert_eq!(d.i2c_ref().writes, [vec![0x80, to]]);
+        assert!(delay.0.is_empty());
+    }
+}
+
+#[test]
+fn brightness_percent_half_is_127() {
+    let mut d = display();
+
+    block_on(d.set_brightness_percent(50)).unwrap();
+
+    assert_eq!(d.i2c_ref().writes, [vec![0x80, 127]]);
+    assert_eq!(d.brightness(), 127);
+}
+
+#[test]
+fn brightness_percent_maps_the_bounds_and_rejects_above_100() {
+    for (percent, brightness) in [(0, 0), (50, 127), (100, 255)] {
+        let mut d = display();
+
+        block_on(d.set_brightness_percent(percent)).unwrap();
+
+        assert_eq!(d.i2c_ref().writes, [vec![0x80, brightness]], "{}%", percent);
+    }
+
+    let mut d = display();
+    assert!(matches!(block_on(d.set_brightness_percent(101)), Err(Error::ValueOutOfRange(101))));
+    assert!(d.i2c_ref().writes.is_empty());
+}
+
+#[test]
+fn blink_produces_the_requested_cycles() {
+    let mut d = display();
+    let mut delay = Delay::default();
+    block_on(d.set_brightness(200)).unwrap();
+    take_writes(&mut d);
+
+    block_on(d.blink(3, 400, 100, &mut delay)).unwrap();
+
+    assert_eq!(d.i2c_ref().writes, [[0x80, 0], [0x80, 200]].repeat(3));
+    assert_eq!(delay.0, [100, 400].repeat(3));
+    assert_eq!(d.brightness(), 200);
+}
+
+#[test]
+fn restore_pushes_the_cached_frame() {
+    let mut d = display();
+    let mut delay = Delay::default();
+    block_on(d.display_number(1234)).unwrap();
+    block_on(d.display_digit(0, 9)).unwrap();
+    block_on(d.display_char(3, 'A')).unwrap();
+    block_on(d.display_dots([false, true, false, false])).unwrap();
+    block_on(d.set_brightness(150)).unwrap();
+    block_on(d.blink(1, 10, 10, &mut delay)).unwrap();
+    take_writes(&mut d);
+
+    block_on(d.restore()).unwrap();
+
+    assert_eq!(
+        d.i2c_ref().writes,
+        [vec![0x89, 0, 9, 0x89, 1, 2, 0x89, 2, 3, 0x89, 3, b'A'], vec![0x85, 0b0000_0100], vec![0x80, 150]]
+    );
+}
+
+#[test]
+fn restore_skips_positions_written_without_a_position() {
+    let mut d = display();
+    block_on(d.send_char('X')).unwrap();
+    block_on(d.display_digit(1, 5)).unwrap();
+    take_writes(&mut d);
+
+    block_on(d.redraw()).unwrap();
+
+    assert_eq!(d.i2c_ref().writes, [vec![0x89, 1, 5], vec![0x85, 0]]);
+}
+
+#[test]
+fn validation_errors_name_the_wrong_argument() {
+    let mut d = display();
+
+    assert!(matches!(block_on(d.display_digit(4, 1)), Err(Error::PositionOutOfRange(4))));
+    assert!(matches!(block_on(d.display_digit(0, 10)), Err(Error::DigitOutOfRange(10))));
+    assert!(matches!(block_on(d.display_number(10000)), Err(Error::NumberTooLarge(10000))));
+    assert!(matches!(block_on(d.display_char(4, 'A')), Err(Error::PositionOutOfRange(4))));
+    assert!(matches!(block_on(d.display_char(0, '~')), Err(Error::UnsupportedChar('~'))));
+    assert!(matches!(block_on(d.set_address(0x40)), Err(Error::AddressOutOfRange(0x40))));
+    assert!(matches!(block_on(d.display_date(13, 1, DateFormat::DDMM, true)), Err(Error::DateOutOfRange(13, 1))));
+    assert!(matches!(block_on(d.display_date(2, 30, DateFormat::DDMM, true)), Err(Error::DateOutOfRange(2, 30))));
+    assert!(d.i2c_ref().writes.is_empty());
+}
+
+#[test]
+fn buffered_digits_are_flushed_in_one_write() {
+    let mut d = display();
+
+    d.begin_frame();
+    for (position, digit) in [4, 3, 2, 1].into_iter().enumerate() {
+        block_on(d.display_digit(position as u8, digit)).unwrap();
+    }
+    assert!(d.i2c_ref().writes.is_empty());
+
+    block_on(d.commit_frame()).unwrap();
+    assert_eq!(d.i2c_ref().writes, [vec![0x89, 0, 4, 0x89, 1, 3, 0x89, 2, 2, 0x89, 3, 1]]);
+
+    // buffering stops with the commit
+    block_on(d.display_digit(0, 9)).unwrap();
+    assert_eq!(d.i2c_ref().writes.len(), 2);
+}
+
+#[test]
+fn buffered_dots_are_flushed_after_the_positions() {
+    let mut d = display();
+
+    d.begin_frame();
+    block_on(d.display_char(0, 'A')).unwrap();
+    block_on(d.set_dot(DOT_LEFT, true)).unwrap();
+    block_on(d.commit_frame()).unwrap();
+
+    assert_eq!(d.i2c_ref().writes, [vec![0x89, 0, b'A'], vec![0x85, 0b0000_0010]]);
+}
+
+#[test]
+fn mode_follows_the_last_successful_set_mode() {
+    let mut d = display();
+    assert_eq!(d.mode(), Mode::Rotate);
+
+    block_on(d.set_mode(Mode::Scroll)).unwrap();
+    assert_eq!(d.mode(), Mode::Scroll);
+    assert_eq!(d.i2c_ref().writes, [vec![0x83, 1]]);
+
+    block_on(d.set_mode(Mode::Rotate)).unwrap();
+    assert_eq!(d.mode(), Mode::Rotate);
+
+    let mut failing = TWIDisplay::new(Mock::failing_at(0), DEFAULT_ADDRESS);
+    assert!(block_on(failing.set_mode(Mode::Scroll)).is_err());
+    assert_eq!(failing.mode(), Mode::Rotate);
+}
+
+#[test]
+fn self_test_lights_everything_then_sweeps_and_restores() {
+    let mut d = display();
+    let mut delay = Delay::default();
+    block_on(d.set_brightness(120)).unwrap();
+    take_writes(&mut d);
+
+    block_on(d.self_test(&mut delay)).unwrap();
+
+    let writes = take_writes(&mut d);
+    assert_eq!(writes[0], [0x84, 0, 0xff, 0x84, 1, 0xff, 0x84, 2, 0xff, 0x84, 3, 0xff]);
+    assert_eq!(writes[1..3], [vec![0x85, 0b0001_1110], vec![0x85, 0b0001_1111]]);
+
+    let sweep: Vec<u8> = writes[3..21].iter().map(|write| write[1]).collect();
+    assert_eq!(sweep, [255, 224, 192, 160, 128, 96, 64, 32, 0, 0, 31, 63, 95, 127, 159, 191, 223, 255]);
+    assert!(writes[3..21].iter().all(|write| write[0] == 0x80));
+
+    assert_eq!(writes[21..], [vec![0x82], vec![0x85, 0], vec![0x80, 120]]);
+    assert_eq!(delay.0[..2], [1000, 1000]);
+    assert_eq!(delay.0[2..], [100; 16]);
+}
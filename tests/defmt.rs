@@ -0,0 +1,15 @@
+//! defmt support, only built with the `defmt` feature
+
+#![cfg(feature = "defmt")]
+
+use akafugu_twidisplay_async::*;
+
+fn assert_format<T: defmt::Format>() {}
+
+#[test]
+fn types_implement_format() {
+    assert_format::<Error<u8>>();
+    assert_format::<Mode>();
+    assert_format::<TempUnits>();
+    assert_format::<DateFormat>();
+}
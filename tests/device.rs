@@ -0,0 +1,152 @@
+//! Device registers, addresses and initialization
+
+mod common;
+
+use akafugu_twidisplay_async::*;
+use common::*;
+use embedded_hal_async::i2c::ErrorKind;
+
+#[test]
+fn firmware_rev_reads_its_register() {
+    let mut d = TWIDisplay::new(Mock::with_reads(&[1]), DEFAULT_ADDRESS);
+
+    assert_eq!(block_on(d.get_firmware_rev()).unwrap(), 1);
+    assert_eq!(d.i2c_ref().writes, [vec![0x8a]]);
+    assert_eq!(d.i2c_ref().addresses, [DEFAULT_ADDRESS]);
+}
+
+#[test]
+fn number_digits_reads_its_register_on_each_call() {
+    let mut d = TWIDisplay::new(Mock::with_reads(&[8, 4]), DEFAULT_ADDRESS);
+
+    assert_eq!(block_on(d.get_number_digits()).unwrap(), 8);
+    assert_eq!(block_on(d.get_number_digits()).unwrap(), 4);
+    assert_eq!(d.i2c_ref().writes, [vec![0x8b], vec![0x8b]]);
+}
+
+#[test]
+fn set_address_rejects_high_addresses() {
+    let mut d = display();
+
+    block_on(d.set_address(0x3f)).unwrap();
+    assert!(matches!(block_on(d.set_address(0x40)), Err(Error::AddressOutOfRange(0x40))));
+
+    assert_eq!(d.i2c_ref().writes, [vec![0x81, 0x3f]]);
+    // the new address is only active after a power cycle
+    assert_eq!(d.address(), DEFAULT_ADDRESS);
+}
+
+#[test]
+fn set_address_unchecked_accepts_any_address() {
+    let mut d = display();
+
+    block_on(d.set_address_unchecked(0x70)).unwrap();
+
+    assert_eq!(d.i2c_ref().writes, [vec![0x81, 0x70]]);
+    assert_eq!(d.address(), DEFAULT_ADDRESS);
+}
+
+#[test]
+fn change_address_now_talks_on_the_new_address() {
+    let mut d = display();
+
+    block_on(d.change_address_now(0x20)).unwrap();
+    assert_eq!(d.address(), 0x20);
+
+    block_on(d.clear_display()).unwrap();
+    assert_eq!(d.i2c_ref().addresses, [DEFAULT_ADDRESS, 0x20]);
+
+    // a rejected address leaves the current one
+    assert!(block_on(d.change_address_now(0x40)).is_err());
+    assert_eq!(d.address(), 0x20);
+}
+
+#[test]
+fn reset_to_default_address_writes_0x12_on_address_zero() {
+    let mut d = TWIDisplay::new(Mock::default(), 0x00);
+
+    block_on(d.reset_to_default_address()).unwrap();
+
+    assert_eq!(d.i2c_ref().writes, [vec![0x81, 0x12]]);
+    assert_eq!(d.i2c_ref().addresses, [0x00]);
+    assert_eq!(d.address(), 0x00);
+}
+
+#[test]
+fn init_applies_the_config_in_order() {
+    let mut d = display();
+
+    block_on(d.init(Config { brightness: 100, mode: Mode::Scroll, clear: true })).unwrap();
+
+    assert_eq!(d.i2c_ref().writes, [vec![0x82], vec![0x83, 1], vec![0x80, 100]]);
+    assert_eq!(d.brightness(), 100);
+    assert_eq!(d.mode(), Mode::Scroll);
+}
+
+#[test]
+fn init_can_keep_the_content() {
+    let mut d = display();
+
+    block_on(d.init(Config { clear: false, ..Config::default() })).unwrap();
+
+    assert_eq!(d.i2c_ref().writes, [vec![0x83, 0], vec![0x80, 255]]);
+}
+
+#[test]
+fn try_new_fails_on_a_nack() {
+    let result = block_on(TWIDisplay::try_new(Mock::failing_at(0), DEFAULT_ADDRESS));
+
+    let Err((error, mut i2c)) = result else { panic!("the probe should fail") };
+    assert!(matches!(error, Error::I2C(ErrorKind::NoAcknowledge(_))));
+
+    // the bus is given back, e.g. to retry at another address
+    i2c.reads.push(1);
+    let d = block_on(TWIDisplay::try_new(i2c, 0x13)).unwrap();
+    assert_eq!(d.address(), 0x13);
+    assert_eq!(d.i2c_ref().addresses, [0x13]);
+}
+
+#[test]
+fn try_new_succeeds_on_an_ack() {
+    let d = block_on(TWIDisplay::try_new(Mock::with_reads(&[1]), DEFAULT_ADDRESS)).unwrap();
+
+    // the probe reads the firmware revision
+    assert_eq!(d.i2c_ref().writes, [vec![0x8a]]);
+    assert_eq!(d.address(), DEFAULT_ADDRESS);
+}
+
+#[test]
+fn is_present_reports_the_probe_without_changing_the_content() {
+    let mut d = TWIDisplay::new(Mock::with_reads(&[1]), DEFAULT_ADDRESS);
+    assert!(block_on(d.is_present()));
+    assert_eq!(d.i2c_ref().writes, [vec![0x8a]]);
+
+    let mut d = TWIDisplay::new(Mock::failing_at(0), DEFAULT_ADDRESS);
+    assert!(!block_on(d.is_present()));
+    assert!(d.i2c_ref().writes.is_empty());
+}
+
+#[test]
+fn reset_clears_then_sets_dots_mode_and_brightness() {
+    let mut d = display();
+    block_on(d.set_colon(true)).unwrap();
+    block_on(d.set_brightness(40)).unwrap();
+    block_on(d.set_mode(Mode::Scroll)).unwrap();
+    take_writes(&mut d);
+
+    block_on(d.reset()).unwrap();
+
+    assert_eq!(d.i2c_ref().writes, [vec![0x82], vec![0x85, 0], vec![0x83, 0], vec![0x80, 255]]);
+    assert_eq!(d.brightness(), 255);
+    assert_eq!(d.mode(), Mode::Rotate);
+}
+
+#[test]
+fn send_raw_forwards_the_bytes_unchanged() {
+    let mut d = display();
+
+    block_on(d.send_raw(&[0x87, 12, 34])).unwrap();
+    block_on(d.send_raw(&[0x8c, 0xff, 0x00, 0x55])).unwrap();
+
+    assert_eq!(d.i2c_ref().writes, [vec![0x87, 12, 34], vec![0x8c, 0xff, 0x00, 0x55]]);
+}
@@ -0,0 +1,76 @@
+//! Dots and colon
+
+mod common;
+
+use akafugu_twidisplay_async::*;
+use common::*;
+
+#[test]
+fn each_single_dot_has_its_own_bit() {
+    let cases = [
+        ([true, false, false, false], 0b0000_0010),
+        ([false, true, false, false], 0b0000_0100),
+        ([false, false, true, false], 0b0000_1000),
+        ([false, false, false, true], 0b0001_0000),
+    ];
+
+    for (dots, bits) in cases {
+        let mut d = display();
+
+        block_on(d.display_dots(dots)).unwrap();
+
+        assert_eq!(d.i2c_ref().writes, [vec![0x85, bits]], "{:?}", dots);
+    }
+}
+
+#[test]
+fn dot_constants_match_the_bits() {
+    let cases = [
+        (DOT_LEFT, 0b0000_0010),
+        (DOT_CENTER_LEFT, 0b0000_0100),
+        (DOT_CENTER_RIGHT, 0b0000_1000),
+        (DOT_RIGHT, 0b0001_0000),
+    ];
+
+    for (dot, bits) in cases {
+        let mut d = display();
+
+        block_on(d.set_dot(dot, true)).unwrap();
+
+        assert_eq!(d.i2c_ref().writes, [vec![0x85, bits]]);
+    }
+}
+
+#[test]
+fn colon_uses_bit_0_and_keeps_the_dots() {
+    let mut d = display();
+    block_on(d.display_dots([false, true, false, false])).unwrap();
+
+    block_on(d.set_colon(true)).unwrap();
+    block_on(d.set_colon(false)).unwrap();
+
+    assert_eq!(d.i2c_ref().writes, [vec![0x85, 0b0000_0100], vec![0x85, 0b0000_0101], vec![0x85, 0b0000_0100]]);
+}
+
+#[test]
+fn dots_keep_the_colon() {
+    let mut d = display();
+    block_on(d.set_colon(true)).unwrap();
+
+    block_on(d.display_dots([true, false, false, false])).unwrap();
+
+    assert_eq!(dots(&d), 0b0000_0011);
+}
+
+#[test]
+fn set_dot_combines_with_the_other_dots() {
+    let mut d = display();
+
+    block_on(d.set_dot(0, true)).unwrap();
+    block_on(d.set_dot(2, true)).unwrap();
+    block_on(d.set_dot(0, false)).unwrap();
+
+    assert_eq!(d.i2c_ref().writes, [vec![0x85, 0b0000_0010], vec![0x85, 0b0000_1010], vec![0x85, 0b0000_1000]]);
+    assert!(matches!(block_on(d.set_dot(4, true)), Err(Error::PositionOutOfRange(4))));
+    assert_eq!(d.i2c_ref().writes.len(), 3);
+}
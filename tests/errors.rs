@@ -0,0 +1,34 @@
+//! Error messages, only built with the `std` feature
+
+#![cfg(feature = "std")]
+
+use akafugu_twidisplay_async::*;
+use embedded_hal_async::i2c::ErrorKind;
+
+#[test]
+fn each_variant_has_a_message() {
+    let cases: [(Error<ErrorKind>, &str); 9] = [
+        (Error::I2C(ErrorKind::Other), "I2C bus error: Other"),
+        (Error::InvalidInputData, "invalid input data"),
+        (Error::UnsupportedChar('~'), "character '~' can't be shown on the display"),
+        (Error::PositionOutOfRange(4), "position 4 out of range"),
+        (Error::DigitOutOfRange(10), "digit 10 out of range"),
+        (Error::NumberTooLarge(10000), "number 10000 doesn't fit on the display"),
+        (Error::AddressOutOfRange(0x40), "address 0x40 out of range, must be below 0x40"),
+        (Error::DateOutOfRange(2, 30), "month 2 and day 30 are not a valid date"),
+        (Error::ValueOutOfRange(60), "value 60 out of range"),
+    ];
+
+    for (error, message) in cases {
+        assert_eq!(error.to_string(), message);
+    }
+}
+
+#[test]
+fn converts_into_a_boxed_std_error() {
+    fn fails() -> Result<(), Box<dyn std::error::Error>> {
+        Err(Error::<ErrorKind>::InvalidInputData)?
+    }
+
+    assert_eq!(fails().unwrap_err().to_string(), "invalid input data");
+}
@@ -0,0 +1,355 @@
+//! Number layouts
+
+mod common;
+
+use akafugu_twidisplay_async::*;
+use common::*;
+
+#[test]
+fn clamped_renders_numbers_that_fit() {
+    let mut d = display();
+
+    block_on(d.display_clamped(-42, 'E')).unwrap();
+    assert_eq!(screen(&d), "- 42");
+
+    block_on(d.display_clamped(9999, 'E')).unwrap();
+    assert_eq!(screen(&d), "9999");
+}
+
+#[test]
+fn clamped_shows_the_glyph_on_overflow() {
+    let mut d = display();
+
+    block_on(d.display_clamped(12345, '-')).unwrap();
+    assert_eq!(screen(&d), "----");
+
+    block_on(d.display_clamped(-1000, 'E')).unwrap();
+    assert_eq!(screen(&d), "EEEE");
+    assert_eq!(d.i2c_ref().writes.len(), 2);
+}
+
+#[test]
+fn clamped_rejects_glyphs_that_cant_be_displayed() {
+    let mut d = display();
+
+    assert!(matches!(block_on(d.display_clamped(12345, '€')), Err(Error::UnsupportedChar('€'))));
+    assert!(matches!(block_on(d.display_clamped(42, '~')), Err(Error::UnsupportedChar('~'))));
+    assert!(d.i2c_ref().writes.is_empty());
+}
+
+#[test]
+fn signed_writes_sign_and_digits_at_once() {
+    for (number, expected) in [(-7, "-  7"), (-42, "- 42"), (123, " 123")] {
+        let mut d = display();
+
+        block_on(d.display_signed(number)).unwrap();
+
+        assert_eq!(d.i2c_ref().writes.len(), 1, "{}", number);
+        assert_eq!(screen(&d), expected);
+    }
+}
+
+#[test]
+fn signed_transcript_for_minus_42() {
+    let mut d = display();
+
+    block_on(d.display_signed(-42)).unwrap();
+
+    assert_eq!(d.i2c_ref().writes, [vec![0x89, 0, b'-', 0x89, 1, b' ', 0x89, 2, 4, 0x89, 3, 2]]);
+}
+
+#[test]
+fn hex_digit_writes_the_raw_value() {
+    let mut d = display();
+
+    block_on(d.display_hex_digit(2, 0x0A)).unwrap();
+
+    assert_eq!(d.i2c_ref().writes, [vec![0x89, 2, 0x0A]]);
+}
+
+#[test]
+fn hex_digit_rejects_invalid_positions_and_values() {
+    let mut d = display();
+
+    assert!(matches!(block_on(d.display_hex_digit(4, 0x0A)), Err(Error::PositionOutOfRange(4))));
+    assert!(matches!(block_on(d.display_hex_digit(0, 0x10)), Err(Error::DigitOutOfRange(0x10))));
+    assert!(d.i2c_ref().writes.is_empty());
+}
+
+#[test]
+fn hex_writes_the_four_nibbles() {
+    let mut d = display();
+
+    block_on(d.display_hex(0xBEEF)).unwrap();
+    assert_eq!(d.i2c_ref().writes, [vec![0x89, 0, 0x0B, 0x89, 1, 0x0E, 0x89, 2, 0x0E, 0x89, 3, 0x0F]]);
+
+    block_on(d.display_hex(0x00FF)).unwrap();
+    assert_eq!(screen(&d), "00FF");
+}
+
+#[test]
+fn padded_blanks_or_keeps_the_leading_zeros() {
+    let mut d = display();
+
+    block_on(d.display_number_padded(23, true)).unwrap();
+    assert_eq!(screen(&d), "0023");
+
+    block_on(d.display_number_padded(23, false)).unwrap();
+    assert_eq!(screen(&d), "  23");
+
+    block_on(d.display_number_padded(5, false)).unwrap();
+    assert_eq!(screen(&d), "   5");
+
+    assert!(matches!(block_on(d.display_number_padded(10000, false)), Err(Error::NumberTooLarge(10000))));
+    assert_eq!(d.i2c_ref().writes.len(), 3);
+}
+
+#[test]
+fn aligned_places_a_single_digit() {
+    let mut d = display();
+
+    block_on(d.display_number_aligned(7, Alignment::Left)).unwrap();
+    assert_eq!(screen(&d), "7   ");
+
+    block_on(d.display_number_aligned(7, Alignment::Right)).unwrap();
+    assert_eq!(screen(&d), "   7");
+
+    block_on(d.display_number_aligned(7, Alignment::Center)).unwrap();
+    assert_eq!(screen(&d), " 7  ");
+
+    block_on(d.display_number_aligned(0, Alignment::Left)).unwrap();
+    assert_eq!(screen(&d), "0   ");
+}
+
+#[test]
+fn signed_renders_the_minus_sign_before_the_magnitude() {
+    let mut d = display();
+
+    block_on(d.display_signed(-5)).unwrap();
+    assert_eq!(screen(&d), "-  5");
+
+    block_on(d.display_signed(-999)).unwrap();
+    assert_eq!(screen(&d), "-999");
+}
+
+#[test]
+fn signed_rejects_numbers_that_dont_fit() {
+    let mut d = display();
+
+    assert!(matches!(block_on(d.display_signed(-1000)), Err(Error::NumberTooLarge(-1000))));
+    assert!(matches!(block_on(d.display_signed(10000)), Err(Error::NumberTooLarge(10000))));
+    assert!(d.i2c_ref().writes.is_empty());
+}
+
+#[test]
+fn flush_writes_the_buffer_then_the_dots() {
+    let mut d = display();
+    let buf = DisplayBuffer { chars: [1, 2, 3, 4], dots: [false, true, false, false] };
+
+    block_on(d.flush(&buf)).unwrap();
+
+    assert_eq!(d.i2c_ref().writes, [vec![0x89, 0, 1, 0x89, 1, 2, 0x89, 2, 3, 0x89, 3, 4], vec![0x85, 0b0000_0100]]);
+}
+
+#[test]
+fn number_needs_one_write_instead_of_four() {
+    let mut per_digit = display();
+    for (position, digit) in [1, 2, 3, 4].into_iter().enumerate() {
+        block_on(per_digit.display_digit(position as u8, digit)).unwrap();
+    }
+
+    let mut d = display();
+    block_on(d.display_number(1234)).unwrap();
+
+    assert_eq!(per_digit.i2c_ref().writes.len(), 4);
+    assert_eq!(d.i2c_ref().writes, [vec![0x89, 0, 1, 0x89, 1, 2, 0x89, 2, 3, 0x89, 3, 4]]);
+    assert_eq!(screen(&d), screen(&per_digit));
+}
+
+#[test]
+fn float_places_the_dot_after_the_integer_part() {
+    let cases = [
+        (23.4, "234 ", 0b0000_0100),
+        (0.5, "05  ", 0b0000_0010),
+        (999.9, "9999", 0b0000_1000),
+        (-0.5, "-05 ", 0b0000_0100),
+    ];
+
+    for (value, expected, dots_value) in cases {
+        let mut d = display();
+
+        block_on(d.display_float(value, 1)).unwrap();
+
+        assert_eq!(screen(&d), expected, "{}", value);
+        assert_eq!(dots(&d), dots_value, "{}", value);
+    }
+}
+
+#[test]
+fn float_rejects_values_that_dont_fit() {
+    let mut d = display();
+
+    assert!(matches!(block_on(d.display_float(1000.0, 1)), Err(Error::NumberTooLarge(1000))));
+    assert!(matches!(block_on(d.display_float(-100.0, 1)), Err(Error::NumberTooLarge(-100))));
+    assert!(matches!(block_on(d.display_float(f32::NAN, 1)), Err(Error::InvalidInputData)));
+    assert!(matches!(block_on(d.display_float(1.0, 4)), Err(Error::ValueOutOfRange(4))));
+    assert!(d.i2c_ref().writes.is_empty());
+}
+
+#[test]
+fn fixed_writes_integer_fraction_and_dot() {
+    let mut d = display();
+
+    block_on(d.display_fixed(23, 4, 1)).unwrap();
+    assert_eq!(d.i2c_ref().writes, [vec![0x89, 0, 2, 0x89, 1, 3, 0x89, 2, 4, 0x89, 3, b' '], vec![0x85, 0b0000_0100]]);
+
+    block_on(d.display_fixed(-5, 3, 2)).unwrap();
+    assert_eq!(screen(&d), " -53");
+    assert_eq!(dots(&d), 0b0000_1000);
+}
+
+#[test]
+fn fixed_rejects_layouts_that_dont_fit() {
+    let mut d = display();
+
+    assert!(matches!(block_on(d.display_fixed(1, 0, 3)), Err(Error::PositionOutOfRange(3))));
+    assert!(matches!(block_on(d.display_fixed(1, 10, 1)), Err(Error::DigitOutOfRange(10))));
+    assert!(matches!(block_on(d.display_fixed(123, 0, 1)), Err(Error::NumberTooLarge(123))));
+    assert!(matches!(block_on(d.display_fixed(-99, 0, 1)), Err(Error::NumberTooLarge(-99))));
+    assert!(d.i2c_ref().writes.is_empty());
+}
+
+#[test]
+fn eight_digit_units_show_long_numbers() {
+    let mut d = TWIDisplay::new_with_digits(Mock::default(), DEFAULT_ADDRESS, 8);
+
+    block_on(d.display_long_number(12345678)).unwrap();
+    assert_eq!(d.i2c_ref().writes.len(), 1);
+    assert_eq!(screen(&d), "12345678");
+
+    block_on(d.display_number(42)).unwrap();
+    assert_eq!(screen(&d), "00000042");
+
+    block_on(d.display_digit(7, 9)).unwrap();
+    assert_eq!(d.i2c_ref().writes.last().unwrap(), &[0x89, 7, 9]);
+
+    assert!(matches!(block_on(d.display_long_number(100_000_000)), Err(Error::NumberTooLarge(100_000_000))));
+    assert!(matches!(block_on(d.display_digit(8, 0)), Err(Error::PositionOutOfRange(8))));
+}
+
+#[test]
+fn four_digit_units_reject_long_numbers() {
+    let mut d = display();
+
+    assert!(matches!(block_on(d.display_long_number(12345)), Err(Error::NumberTooLarge(12345))));
+    assert!(d.i2c_ref().writes.is_empty());
+}
+
+#[test]
+fn send_hex_digit_sends_the_raw_value() {
+    let mut d = display();
+
+    block_on(d.send_hex_digit(0x0C)).unwrap();
+    assert!(matches!(block_on(d.send_hex_digit(0x10)), Err(Error::DigitOutOfRange(0x10))));
+
+    assert_eq!(d.i2c_ref().writes, [vec![0x0C]]);
+}
+
+#[test]
+fn saturating_marks_overflow_with_the_rightmost_dot() {
+    for (number, expected, dot) in [(9999, "9999", 0), (12345, "9999", 0b0001_0000), (0, "0000", 0)] {
+        let mut d = display();
+
+        block_on(d.display_number_saturating(number)).unwrap();
+
+        assert_eq!(screen(&d), expected, "{}", number);
+        assert_eq!(dots(&d), dot, "{}", number);
+    }
+}
+
+#[test]
+fn saturating_clears_the_dot_once_the_number_fits() {
+    let mut d = display();
+
+    block_on(d.display_number_saturating(12345)).unwrap();
+    block_on(d.display_number_saturating(42)).unwrap();
+
+    assert_eq!(screen(&d), "0042");
+    assert_eq!(dots(&d), 0);
+}
+
+#[test]
+fn word_sends_the_value_high_byte_first() {
+    let mut d = display();
+    block_on(d.display_number(5678)).unwrap();
+    take_writes(&mut d);
+
+    block_on(d.display_word(1234)).unwrap();
+    assert_eq!(d.i2c_ref().writes, [vec![0x88, 0x04, 0xd2]]);
+    assert!(matches!(block_on(d.display_word(10000)), Err(Error::NumberTooLarge(10000))));
+
+    // the firmware decides the layout, so a redraw has no positions to write
+    take_writes(&mut d);
+    block_on(d.redraw()).unwrap();
+    assert_eq!(d.i2c_ref().writes, [vec![0x85, 0]]);
+}
+
+#[test]
+fn octet_is_right_aligned_before_the_dot() {
+    for (value, expected) in [(5, "  5 "), (192, "192 "), (255, "255 "), (0, "  0 ")] {
+        let mut d = display();
+
+        block_on(d.display_octet(value)).unwrap();
+
+        assert_eq!(screen(&d), expected, "{}", value);
+        assert_eq!(dots(&d), 0b0000_1000, "{}", value);
+    }
+}
+
+#[test]
+fn with_dots_writes_the_digits_then_the_dots() {
+    let mut d = display();
+
+    block_on(d.display_number_with_dots(1234, [false, true, false, true])).unwrap();
+    assert_eq!(d.i2c_ref().writes, [vec![0x89, 0, 1, 0x89, 1, 2, 0x89, 2, 3, 0x89, 3, 4], vec![0x85, 0b0001_0100]]);
+
+    take_writes(&mut d);
+    block_on(d.display_text_with_dots("AB", [true, false, false, false])).unwrap();
+    assert_eq!(d.i2c_ref().writes.len(), 2);
+    assert_eq!(screen(&d), "AB  ");
+    assert_eq!(dots(&d), 0b0000_0010);
+}
+
+#[test]
+fn digits_are_written_at_once_and_validated() {
+    let mut d = display();
+
+    block_on(d.display_digits([1, 2, 3, 4])).unwrap();
+    assert_eq!(d.i2c_ref().writes, [vec![0x89, 0, 1, 0x89, 1, 2, 0x89, 2, 3, 0x89, 3, 4]]);
+
+    assert!(matches!(block_on(d.display_digits([1, 2, 3, 10])), Err(Error::DigitOutOfRange(10))));
+    assert_eq!(d.i2c_ref().writes.len(), 1);
+}
+
+#[test]
+fn two_digits_have_a_leading_zero_and_leave_the_rest() {
+    let mut d = display();
+    block_on(d.display_text_fixed("AB  ")).unwrap();
+    take_writes(&mut d);
+
+    block_on(d.display_two_digits(2, 7)).unwrap();
+    assert_eq!(d.i2c_ref().writes, [vec![0x89, 2, 0, 0x89, 3, 7]]);
+
+    block_on(d.display_two_digits(0, 42)).unwrap();
+    assert_eq!(screen(&d), "4207");
+}
+
+#[test]
+fn two_digits_reject_positions_without_room_for_both() {
+    let mut d = display();
+
+    assert!(matches!(block_on(d.display_two_digits(3, 7)), Err(Error::PositionOutOfRange(3))));
+    assert!(matches!(block_on(d.display_two_digits(255u8, 7)), Err(Error::PositionOutOfRange(255))));
+    assert!(matches!(block_on(d.display_two_digits(0, 100)), Err(Error::NumberTooLarge(100))));
+    assert!(d.i2c_ref().writes.is_empty());
+}
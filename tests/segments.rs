@@ -0,0 +1,231 @@
+//! Raw segment writes: frames, custom characters and segment-based widgets
+
+mod common;
+
+use akafugu_twidisplay_async::*;
+use common::*;
+
+#[test]
+fn write_frame_sends_all_positions_in_one_write() {
+    let mut d = display();
+    let frame = [0x3f, 0x06, 0x5b, 0x4f]; // 0, 1, 2, 3
+
+    block_on(d.write_frame(&frame)).unwrap();
+
+    assert_eq!(
+        d.i2c_ref().writes,
+        [vec![0x84, 0, 0x3f, 0x84, 1, 0x06, 0x84, 2, 0x5b, 0x84, 3, 0x4f]]
+    );
+    assert_eq!(screen(&d), "0123");
+}
+
+#[test]
+fn write_frame_needs_one_write_instead_of_four() {
+    let frame = [0x76, 0x79, 0x38, 0x38]; // HELL
+
+    let mut batched = display();
+    block_on(batched.write_frame(&frame)).unwrap();
+
+    let mut single = display();
+    for (position, mask) in frame.into_iter().enumerate() {
+        block_on(single.define_custom_char(position as u8, mask)).unwrap();
+    }
+
+    assert_eq!(batched.i2c_ref().writes.len(), 1);
+    assert_eq!(single.i2c_ref().writes.len(), 4);
+    assert_eq!(screen(&batched), screen(&single));
+    assert_eq!(batched.i2c_ref().writes.concat(), single.i2c_ref().writes.concat());
+}
+
+#[test]
+fn invert_flips_segments_a_to_g_of_raw_writes() {
+    let mut d = display();
+    d.set_invert(true);
+
+    block_on(d.define_custom_char(0, SegmentMask::SEG_A | SegmentMask::SEG_DP)).unwrap();
+    block_on(d.write_frame(&[0x00, 0x7f, 0x06, 0xff])).unwrap();
+
+    assert_eq!(d.i2c_ref().writes[0], [0x84, 0, 0xfe]);
+    assert_eq!(d.i2c_ref().writes[1], [0x84, 0, 0x7f, 0x84, 1, 0x00, 0x84, 2, 0x79, 0x84, 3, 0x80]);
+}
+
+#[test]
+fn invert_applies_to_characters_and_defaults_to_off() {
+    let mut d = display();
+
+    block_on(d.define_custom_char(0, 0x06)).unwrap();
+    d.set_invert(true);
+    block_on(d.display_char(1, 'A')).unwrap();
+    block_on(d.display_digit(2, 1)).unwrap();
+    block_on(d.display_char(3, 'K')).unwrap(); // not legible on 7 segments, left to the firmware
+
+    assert_eq!(
+        d.i2c_ref().writes,
+        [vec![0x84, 0, 0x06], vec![0x84, 1, !0x77 & 0x7f], vec![0x84, 2, !0x06 & 0x7f], vec![0x89, 3, b'K']]
+    );
+}
+
+#[test]
+fn battery_maps_levels_to_cells() {
+    const B: u8 = 0x7c;
+    const CELL: u8 = SegmentMask::SEG_A | SegmentMask::SEG_D | SegmentMask::SEG_G;
+
+    for (percent, cells) in [(0, 0), (1, 1), (33, 1), (34, 2), (66, 2), (67, 3), (100, 3), (255, 3)] {
+        let mut d = display();
+
+        block_on(d.display_battery(percent)).unwrap();
+
+        let mut expected = [B, 0, 0, 0];
+        expected[1..=cells].fill(CELL);
+        let masks: Vec<u8> = d.i2c_ref().writes[0].chunks(3).map(|command| command[2]).collect();
+        assert_eq!(masks, expected, "{}%", percent);
+    }
+}
+
+#[test]
+fn custom_char_sends_slot_and_mask() {
+    let mut d = display();
+    let degree = SegmentMask::SEG_A | SegmentMask::SEG_B | SegmentMask::SEG_F | SegmentMask::SEG_G;
+
+    block_on(d.define_custom_char(3, degree)).unwrap();
+    block_on(d.define_custom_char(0, SegmentMask::SEG_DP)).unwrap();
+
+    assert_eq!(d.i2c_ref().writes, [vec![0x84, 3, 0b0110_0011], vec![0x84, 0, 0b1000_0000]]);
+    assert!(matches!(block_on(d.define_custom_char(4, degree)), Err(Error::PositionOutOfRange(4))));
+}
+
+/// Segment masks of a write_frame() write
+fn masks(write: &[u8]) -> Vec<u8> {
+    write.chunks(3).map(|command| command[2]).collect()
+}
+
+#[test]
+fn bar_fills_bottom_segments_from_the_left() {
+    for (percent, expected) in [(0, [0, 0, 0, 0]), (50, [0x08, 0x08, 0, 0]), (100, [0x08; 4]), (1, [0x08, 0, 0, 0])] {
+        let mut d = display();
+
+        block_on(d.display_bar(percent)).unwrap();
+
+        assert_eq!(masks(&d.i2c_ref().writes[0]), expected, "{}%", percent);
+    }
+
+    assert!(matches!(block_on(display().display_bar(101)), Err(Error::ValueOutOfRange(101))));
+}
+
+#[test]
+fn spinner_goes_clockwise_through_six_frames() {
+    let mut d = display();
+
+    for frame in 0..6 {
+        block_on(d.spinner_frame(2, frame)).unwrap();
+    }
+
+    let expected = [
+        SegmentMask::SEG_A,
+        SegmentMask::SEG_B,
+        SegmentMask::SEG_C,
+        SegmentMask::SEG_D,
+        SegmentMask::SEG_E,
+        SegmentMask::SEG_F,
+    ];
+    let writes: Vec<Vec<u8>> = expected.iter().map(|mask| vec![0x84, 2, *mask]).collect();
+    assert_eq!(d.i2c_ref().writes, writes);
+}
+
+#[test]
+fn spinner_rejects_invalid_frames_and_positions() {
+    let mut d = display();
+
+    assert!(matches!(block_on(d.spinner_frame(0, 6)), Err(Error::ValueOutOfRange(6))));
+    assert!(matches!(block_on(d.spinner_frame(4, 0)), Err(Error::PositionOutOfRange(4))));
+    assert!(d.i2c_ref().writes.is_empty());
+}
+
+#[test]
+fn legible_chars_and_their_masks() {
+    for ch in ['0', '9', 'A', 'b', 'H', 'L', 'P', 'U', '-', ' '] {
+        assert!(char_is_supported(ch), "{:?}", ch);
+    }
+    for ch in ['M', 'W', 'X', 'K', '!', 'a', 'z', '€'] {
+        assert!(!char_is_supported(ch), "{:?}", ch);
+    }
+
+    assert_eq!(char_segments('8'), Some(0x7f));
+    assert_eq!(char_segments('L'), Some(SegmentMask::SEG_D | SegmentMask::SEG_E | SegmentMask::SEG_F));
+    assert_eq!(char_segments('M'), None);
+    assert_eq!(supported_chars().count(), CHAR_SEGMENTS.len());
+    assert!(supported_chars().all(is_displayable));
+}
+
+#[test]
+fn play_frames_writes_each_frame_once_without_repeat() {
+    let mut d = display();
+    let mut delay = Delay::default();
+    let frames = [[SegmentMask::SEG_A; 4], [SegmentMask::SEG_D; 4]];
+
+    block_on(d.play_frames(&frames, 250, false, &mut delay)).unwrap();
+
+    let writes = &d.i2c_ref().writes;
+    assert_eq!(writes.len(), 2);
+    assert_eq!(masks(&writes[0]), frames[0]);
+    assert_eq!(masks(&writes[1]), frames[1]);
+    assert_eq!(delay.0, [250, 250]);
+}
+
+#[test]
+fn play_frames_returns_on_an_empty_animation() {
+    let mut d = display();
+    let mut delay = Delay::default();
+
+    block_on(d.play_frames(&[], 250, true, &mut delay)).unwrap();
+
+    assert!(d.i2c_ref().writes.is_empty());
+}
+
+#[test]
+fn position_only_accepts_the_four_positions() {
+    assert_eq!(Position::new(0), Some(Position::P0));
+    assert_eq!(Position::new(3), Some(Position::P3));
+    assert_eq!(Position::new(4), None);
+    assert_eq!(Position::P2.index(), 2);
+
+    let mut d = display();
+    block_on(d.display_digit(Position::P1, 7)).unwrap();
+    // plain numbers are still accepted, and checked when used
+    assert!(matches!(block_on(d.display_digit(5, 7)), Err(Error::PositionOutOfRange(5))));
+    assert_eq!(d.i2c_ref().writes, [vec![0x89, 1, 7]]);
+}
+
+#[test]
+fn flipped_orientation_reverses_the_positions() {
+    let mut d = display();
+    d.set_orientation(true);
+
+    block_on(d.display_char(0, 'A')).unwrap();
+    block_on(d.display_digit(1, 7)).unwrap();
+    block_on(d.define_custom_char(3, SegmentMask::SEG_G)).unwrap();
+
+    let positions: Vec<u8> = d.i2c_ref().writes.iter().map(|write| write[1]).collect();
+    assert_eq!(positions, [3, 2, 0]);
+
+    d.set_orientation(false);
+    take_writes(&mut d);
+    block_on(d.display_char(0, 'A')).unwrap();
+    assert_eq!(d.i2c_ref().writes, [vec![0x89, 0, b'A']]);
+}
+
+#[test]
+fn segment_flip_rotates_the_segments() {
+    assert_eq!(segment_flip(0x5b), 0x5b); // 2
+    assert_eq!(segment_flip(0x7d), 0x6f); // 6 becomes 9
+    assert_eq!(segment_flip(SegmentMask::SEG_A | SegmentMask::SEG_DP), SegmentMask::SEG_D | SegmentMask::SEG_DP);
+    for mask in 0..=u8::MAX {
+        assert_eq!(segment_flip(segment_flip(mask)), mask);
+    }
+
+    let mut d = display();
+    d.set_orientation(true);
+    block_on(d.display_char(0, '2')).unwrap();
+    block_on(d.display_digit(1, 6)).unwrap();
+    assert_eq!(d.i2c_ref().writes, [vec![0x84, 3, 0x5b], vec![0x84, 2, 0x6f]]);
+}
@@ -0,0 +1,232 @@
+//! Temperature and other values with units
+
+mod common;
+
+use akafugu_twidisplay_async::*;
+use common::*;
+
+#[test]
+fn scroll_range_shows_low_separator_high() {
+    let mut d = display();
+    let mut delay = Delay::default();
+
+    block_on(d.scroll_range(-3, 12, TempUnits::Celsius, &mut delay, 1000)).unwrap();
+
+    let writes = &d.i2c_ref().writes;
+    assert_eq!(writes.len(), 9);
+    assert_eq!(screen_of(&writes[..4], 4), " -3C");
+    assert_eq!(screen_of(&writes[..5], 4), " -- ");
+    assert_eq!(screen_of(writes, 4), " 12C");
+    assert_eq!(delay.0, [1000, 1000, 1000]);
+}
+
+#[test]
+fn scroll_range_validates_both_values() {
+    let mut d = display();
+    let mut delay = Delay::default();
+
+    let mut range = |low, high| block_on(d.scroll_range(low, high, TempUnits::Celsius, &mut delay, 1000));
+    assert!(matches!(range(-100, 12), Err(Error::NumberTooLarge(-100))));
+    assert!(matches!(range(-3, 1000), Err(Error::NumberTooLarge(1000))));
+    assert!(matches!(range(12, -3), Err(Error::InvalidInputData)));
+    assert!(d.i2c_ref().writes.is_empty());
+    assert!(delay.0.is_empty());
+}
+
+#[test]
+fn value_unit2_shows_value_and_two_letter_unit() {
+    let mut d = display();
+
+    block_on(d.display_value_unit2(42, ['d', 'b'])).unwrap();
+    assert_eq!(d.i2c_ref().writes, [vec![0x89, 0, 4, 0x89, 1, 2, 0x89, 2, b'd', 0x89, 3, b'b']]);
+
+    block_on(d.display_value_unit2(7, ['P', 'a'])).unwrap();
+    assert_eq!(screen(&d), " 7Pa");
+}
+
+#[test]
+fn value_unit2_rejects_large_values_and_unknown_glyphs() {
+    let mut d = display();
+
+    assert!(matches!(block_on(d.display_value_unit2(100, ['d', 'b'])), Err(Error::NumberTooLarge(100))));
+    assert!(matches!(block_on(d.display_value_unit2(42, ['z', '\u{1}'])), Err(Error::UnsupportedChar('z'))));
+    assert!(matches!(block_on(d.display_value_unit2(42, ['H', '\u{1}'])), Err(Error::UnsupportedChar('\u{1}'))));
+    assert!(matches!(block_on(d.display_value_unit2(50, ['H', 'z'])), Err(Error::UnsupportedChar('z'))));
+    assert!(d.i2c_ref().writes.is_empty());
+}
+
+#[test]
+fn temperature_decimal_shows_tenths_and_the_unit() {
+    let mut d = display();
+
+    block_on(d.display_temperature_decimal(234, TempUnits::Celsius)).unwrap();
+    assert_eq!(screen(&d), "234C");
+    assert_eq!(dots(&d), 0b0000_0100);
+
+    block_on(d.display_temperature_decimal(-5, TempUnits::Fahrenheit)).unwrap();
+    assert_eq!(screen(&d), "-05F");
+    assert_eq!(dots(&d), 0b0000_0100);
+
+    block_on(d.display_temperature_decimal(7, TempUnits::Celsius)).unwrap();
+    assert_eq!(screen(&d), " 07C");
+}
+
+#[test]
+fn temperature_decimal_rejects_values_out_of_range() {
+    let mut d = display();
+
+    assert!(matches!(block_on(d.display_temperature_decimal(1000, TempUnits::Celsius)), Err(Error::NumberTooLarge(1000))));
+    assert!(matches!(block_on(d.display_temperature_decimal(-100, TempUnits::Celsius)), Err(Error::NumberTooLarge(-100))));
+    assert!(d.i2c_ref().writes.is_empty());
+}
+
+#[test]
+fn temperature_converted_to_fahrenheit() {
+    let mut d = display();
+
+    block_on(d.display_temperature_converted(100, TempUnits::Fahrenheit, None, None)).unwrap();
+    assert_eq!(screen(&d), "212F");
+
+    block_on(d.display_temperature_converted(0, TempUnits::Fahrenheit, None, None)).unwrap();
+    assert_eq!(screen(&d), " 32F");
+
+    block_on(d.display_temperature_converted(21, TempUnits::Celsius, None, None)).unwrap();
+    assert_eq!(screen(&d), " 21C");
+}
+
+#[test]
+fn temperature_converted_applies_thresholds_to_fahrenheit() {
+    let mut d = display();
+
+    // 30 C is 86 F: above an 80 F threshold, though below 80 as Celsius
+    block_on(d.display_temperature_converted(30, TempUnits::Fahrenheit, None, Some(80))).unwrap();
+    assert_eq!(screen(&d), "-HH-");
+
+    // 20 C is 68 F: above a 50 F lower threshold, though 20 is below it
+    block_on(d.display_temperature_converted(20, TempUnits::Fahrenheit, Some(50), None)).unwrap();
+    assert_eq!(screen(&d), " 68F");
+}
+
+#[test]
+fn percentage_is_followed_by_p() {
+    for (percent, expected) in [(5, "  5P"), (85, " 85P"), (100, "100P"), (0, "  0P")] {
+        let mut d = display();
+
+        block_on(d.display_percentage(percent)).unwrap();
+
+        assert_eq!(d.i2c_ref().writes.len(), 1);
+        assert_eq!(screen(&d), expected);
+    }
+
+    assert!(matches!(block_on(display().display_percentage(101)), Err(Error::ValueOutOfRange(101))));
+}
+
+#[test]
+fn negative_temperatures_keep_the_sign_next_to_the_digits() {
+    for (temperature, expected) in [(-5, " -5C"), (-50, "-50C"), (-99, "-99C"), (-1, " -1C")] {
+        let mut d = display();
+
+        block_on(d.display_temperature(temperature, TempUnits::Celsius, None, None)).unwrap();
+
+        assert_eq!(screen(&d), expected, "{}", temperature);
+    }
+}
+
+#[test]
+fn hysteresis_holds_the_high_state_around_the_threshold() {
+    let mut d = display();
+    let sequence = [(50, " 50C"), (51, "-HH-"), (50, "-HH-"), (48, "-HH-"), (47, " 47C"), (49, " 49C"), (51, "-HH-")];
+
+    for (temperature, expected) in sequence {
+        block_on(d.display_temperature_hyst(temperature, TempUnits::Celsius, None, Some(50), 2)).unwrap();
+
+        assert_eq!(screen(&d), expected, "{}", temperature);
+    }
+}
+
+#[test]
+fn hysteresis_holds_the_low_state_around_the_threshold() {
+    let mut d = display();
+    let sequence = [(-10, "-10C"), (-11, "-LL-"), (-9, "-LL-"), (-8, "-LL-"), (-7, " -7C"), (-10, "-10C")];
+
+    for (temperature, expected) in sequence {
+        block_on(d.display_temperature_hyst(temperature, TempUnits::Celsius, Some(-10), None, 2)).unwrap();
+
+        assert_eq!(screen(&d), expected, "{}", temperature);
+    }
+}
+
+#[test]
+fn value_with_unit_shows_the_unit_and_thresholds() {
+    let mut d = display();
+
+    block_on(d.display_value_with_unit(12, 'A', None, None)).unwrap();
+    assert_eq!(screen(&d), " 12A");
+
+    block_on(d.display_value_with_unit(12, 'A', None, Some(10))).unwrap();
+    assert_eq!(screen(&d), "-HH-");
+
+    block_on(d.display_value_with_unit(12, 'A', Some(20), None)).unwrap();
+    assert_eq!(screen(&d), "-LL-");
+
+    take_writes(&mut d);
+    assert!(matches!(block_on(d.display_value_with_unit(12, '€', None, None)), Err(Error::UnsupportedChar('€'))));
+    assert!(d.i2c_ref().writes.is_empty());
+}
+
+#[test]
+fn temperatures_are_right_aligned_before_the_unit() {
+    for (temperature, expected) in [(5, "  5C"), (25, " 25C"), (100, "100C"), (-5, " -5C")] {
+        let mut d = display();
+
+        block_on(d.display_temperature(temperature, TempUnits::Celsius, None, None)).unwrap();
+
+        assert_eq!(screen(&d), expected, "{}", temperature);
+    }
+}
+
+#[test]
+fn trend_is_shown_on_the_outer_dots() {
+    for (trend, expected) in [(Trend::Rising, 0b0001_0000), (Trend::Falling, 0b0000_0010), (Trend::Steady, 0)] {
+        let mut d = display();
+
+        block_on(d.display_temperature_with_trend(21, TempUnits::Celsius, trend)).unwrap();
+
+        assert_eq!(screen(&d), " 21C", "{:?}", trend);
+        assert_eq!(dots(&d), expected, "{:?}", trend);
+        assert_eq!(d.i2c_ref().writes.len(), transaction_count(Operation::DisplayTemperatureWithTrend));
+    }
+}
+
+#[test]
+fn clamped_temperature_shows_the_marker_or_the_threshold() {
+    let mut d = display();
+
+    block_on(d.display_temperature_clamped(250, TempUnits::Celsius, None, Some(60), ClampBehavior::ShowMarker)).unwrap();
+    assert_eq!(screen(&d), "-HH-");
+
+    block_on(d.display_temperature_clamped(250, TempUnits::Celsius, None, Some(60), ClampBehavior::Clamp)).unwrap();
+    assert_eq!(screen(&d), " 60C");
+
+    block_on(d.display_temperature_clamped(-20, TempUnits::Celsius, Some(-5), None, ClampBehavior::Clamp)).unwrap();
+    assert_eq!(screen(&d), " -5C");
+
+    block_on(d.display_temperature_clamped(25, TempUnits::Celsius, None, Some(60), ClampBehavior::Clamp)).unwrap();
+    assert_eq!(screen(&d), " 25C");
+}
+
+#[test]
+fn clamped_temperature_blanks_the_positions_and_the_dots() {
+    let mut d = display();
+    block_on(d.display_temperature_with_trend(21, TempUnits::Celsius, Trend::Rising)).unwrap();
+    take_writes(&mut d);
+
+    block_on(d.display_temperature_clamped(250, TempUnits::Celsius, None, Some(60), ClampBehavior::Blank)).unwrap();
+    assert_eq!(
+        d.i2c_ref().writes,
+        [vec![0x89, 0, b' ', 0x89, 1, b' ', 0x89, 2, b' ', 0x89, 3, b' '], vec![0x85, 0]]
+    );
+
+    block_on(d.display_temperature_clamped(25, TempUnits::Celsius, None, Some(60), ClampBehavior::Blank)).unwrap();
+    assert_eq!(screen(&d), " 25C");
+}
@@ -0,0 +1,257 @@
+//! Characters and text
+
+mod common;
+
+use akafugu_twidisplay_async::*;
+use common::*;
+
+#[test]
+fn redraw_text_replaces_the_display_in_one_write() {
+    let mut d = display();
+
+    block_on(d.redraw_text("RUN")).unwrap();
+
+    assert_eq!(d.i2c_ref().writes, [vec![0x89, 0, b'R', 0x89, 1, b'U', 0x89, 2, b'N', 0x89, 3, b' ']]);
+}
+
+#[test]
+fn redraw_text_pads_and_truncates() {
+    let mut d = display();
+
+    block_on(d.redraw_text("AB")).unwrap();
+    assert_eq!(screen(&d), "AB  ");
+
+    block_on(d.redraw_text("ABCDEF")).unwrap();
+    assert_eq!(screen(&d), "ABCD");
+
+    block_on(d.redraw_text("")).unwrap();
+    assert_eq!(screen(&d), "    ");
+}
+
+#[test]
+fn code_writes_all_four_positions_at_once() {
+    let mut d = display();
+
+    block_on(d.display_code(['E', '0', '4', 'A'])).unwrap();
+
+    assert_eq!(d.i2c_ref().writes, [vec![0x89, 0, b'E', 0x89, 1, b'0', 0x89, 2, b'4', 0x89, 3, b'A']]);
+}
+
+#[test]
+fn code_rejects_the_first_invalid_char_before_writing() {
+    let mut d = display();
+
+    assert!(matches!(block_on(d.display_code(['A', 'z', '~', 'B'])), Err(Error::UnsupportedChar('z'))));
+    assert!(d.i2c_ref().writes.is_empty());
+}
+
+#[test]
+fn scroll_text_shifts_a_window_across_the_text() {
+    let mut d = display();
+    let mut delay = Delay::default();
+
+    block_on(d.scroll_text("ABCDEF", &mut delay, 200)).unwrap();
+
+    let windows: Vec<String> = d.i2c_ref().writes.chunks(1).map(|write| screen_of(write, 4)).collect();
+    assert_eq!(windows, ["   A", "  AB", " ABC", "ABCD", "BCDE", "CDEF", "DEF ", "EF  ", "F   "]);
+    assert_eq!(delay.0, [200; 9]);
+}
+
+#[test]
+fn scroll_text_shows_short_text_once() {
+    let mut d = display();
+    let mut delay = Delay::default();
+
+    block_on(d.scroll_text("AB", &mut delay, 200)).unwrap();
+
+    assert_eq!(d.i2c_ref().writes.len(), 1);
+    assert_eq!(screen(&d), "AB  ");
+    assert!(delay.0.is_empty());
+}
+
+#[test]
+fn clear_digit_writes_a_space() {
+    let mut d = display();
+    block_on(d.display_number(1234)).unwrap();
+    take_writes(&mut d);
+
+    block_on(d.clear_digit(2)).unwrap();
+
+    assert_eq!(d.i2c_ref().writes, [vec![0x89, 2, b' ']]);
+    assert!(matches!(block_on(d.clear_digit(4)), Err(Error::PositionOutOfRange(4))));
+}
+
+#[test]
+fn writer_buffers_until_flush() {
+    use core::fmt::Write;
+
+    let mut d = display();
+    let mut writer = DisplayWriter::new();
+
+    write!(writer, "AB").unwrap();
+    assert!(d.i2c_ref().writes.is_empty());
+
+    block_on(writer.flush(&mut d)).unwrap();
+    assert_eq!(d.i2c_ref().writes.len(), 1);
+    assert_eq!(screen(&d), "AB  ");
+
+    // the buffer is emptied by the flush
+    write!(writer, "{:>4}", 7).unwrap();
+    block_on(writer.flush(&mut d)).unwrap();
+    assert_eq!(screen(&d), "   7");
+}
+
+#[test]
+fn writer_rejects_overflow_and_unsupported_chars() {
+    use core::fmt::Write;
+
+    assert!(write!(DisplayWriter::new(), "ABCDE").is_err());
+    assert!(write!(DisplayWriter::new(), "~").is_err());
+}
+
+#[test]
+fn text_paths_reject_chars_that_cant_be_displayed() {
+    let mut d = display();
+    let mut delay = Delay::default();
+
+    assert!(matches!(block_on(d.redraw_text("A€")), Err(Error::UnsupportedChar('€'))));
+    assert!(matches!(block_on(d.scroll_text("ABCDE~", &mut delay, 10)), Err(Error::UnsupportedChar('~'))));
+    let buf = DisplayBuffer { chars: [b'A', 0x7f, 1, 2], dots: [false; 4] };
+    assert!(matches!(block_on(d.flush(&buf)), Err(Error::UnsupportedChar('\u{7f}'))));
+
+    assert!(d.i2c_ref().writes.is_empty());
+    assert!(delay.0.is_empty());
+}
+
+#[test]
+fn chars_are_checked_against_the_displayable_range() {
+    assert!(is_displayable('A'));
+    assert!(is_displayable(' '));
+    assert!(!is_displayable('€'));
+    assert!(!is_displayable('\u{1}'));
+    // lowercase letters are sent as they are, up to 'y'
+    assert!(is_displayable('a'));
+    assert!(is_displayable('y'));
+    assert!(!is_displayable('z'));
+
+    let mut d = display();
+    block_on(d.display_char(0, 'A')).unwrap();
+    block_on(d.display_char(1, 'b')).unwrap();
+    assert!(matches!(block_on(d.display_char(2, '€')), Err(Error::UnsupportedChar('€'))));
+    assert_eq!(d.i2c_ref().writes, [vec![0x89, 0, b'A'], vec![0x89, 1, b'b']]);
+}
+
+/// Characters sent without a position, e.g. by `send_text()`
+fn sent(writes: &[Vec<u8>]) -> String {
+    writes.iter().map(|write| write[0] as char).collect()
+}
+
+#[test]
+fn sanitized_keeps_only_legible_chars() {
+    let mut d = display();
+
+    block_on(d.send_text_sanitized("Hi!")).unwrap();
+    assert_eq!(d.i2c_ref().writes, [vec![b'H'], vec![b'I'], vec![b' ']]);
+
+    let cases = [("bdM", "bd "), ("hello", "hELLo"), ("Wz€", "   "), ("rtuycq", "rtuycq"), ("ae", "AE")];
+
+    for (text, expected) in cases {
+        let mut d = display();
+
+        block_on(d.send_text_sanitized(text)).unwrap();
+
+        assert_eq!(sent(&d.i2c_ref().writes), expected, "{}", text);
+    }
+}
+
+#[test]
+fn upper_folds_ascii_letters_only() {
+    let mut d = display();
+
+    block_on(d.send_text_upper("hello world")).unwrap();
+    assert_eq!(sent(&d.i2c_ref().writes), "HELLO WORLD");
+
+    let mut d = display();
+    block_on(d.send_text_upper("a1-Z")).unwrap();
+    assert_eq!(sent(&d.i2c_ref().writes), "A1-Z");
+}
+
+#[test]
+fn text_fixed_pads_truncates_and_matches_redraw_text() {
+    for (text, expected) in [("AB", "AB  "), ("ABCDE", "ABCD")] {
+        let mut fixed = display();
+        let mut redraw = display();
+
+        block_on(fixed.display_text_fixed(text)).unwrap();
+        block_on(redraw.redraw_text(text)).unwrap();
+
+        assert_eq!(screen(&fixed), expected);
+        assert_eq!(fixed.i2c_ref().writes, redraw.i2c_ref().writes);
+    }
+
+    // the same in both modes, which are left as they are
+    let mut d = display();
+    block_on(d.set_mode(Mode::Scroll)).unwrap();
+    take_writes(&mut d);
+    block_on(d.display_text_fixed("AB")).unwrap();
+    assert_eq!(d.i2c_ref().writes.len(), 1);
+    assert_eq!(screen(&d), "AB  ");
+}
+
+#[test]
+fn display_char_is_validated_on_construction() {
+    let a = DisplayChar::new('A').unwrap();
+    assert_eq!(a.as_char(), Some('A'));
+    assert_eq!(a.segments(), None);
+    assert_eq!(DisplayChar::new('€'), None);
+    assert_eq!(DisplayChar::new('z'), None);
+
+    let dash = DisplayChar::from(SegmentMask::SEG_G);
+    assert_eq!(dash.as_char(), None);
+    assert_eq!(dash.segments(), Some(SegmentMask::SEG_G));
+
+    let mut d = display();
+    block_on(d.display_char(0, a)).unwrap();
+    block_on(d.display_char(1, dash)).unwrap();
+    assert_eq!(d.i2c_ref().writes, [vec![0x89, 0, b'A'], vec![0x84, 1, SegmentMask::SEG_G]]);
+}
+
+#[test]
+fn static_text_is_the_same_in_both_modes() {
+    let mut rotate = display();
+    let mut scroll = display();
+    block_on(scroll.set_mode(Mode::Scroll)).unwrap();
+    take_writes(&mut scroll);
+
+    block_on(rotate.display_static_text("STOP")).unwrap();
+    block_on(scroll.display_static_text("STOP")).unwrap();
+
+    assert_eq!(screen(&scroll), "STOP");
+    assert_eq!(scroll.i2c_ref().writes, rotate.i2c_ref().writes);
+    assert_eq!(scroll.mode(), Mode::Scroll);
+
+    let mut wide = TWIDisplay::new_with_digits(Mock::default(), DEFAULT_ADDRESS, 8);
+    block_on(wide.display_static_text("AB")).unwrap();
+    assert_eq!(wide.i2c_ref().writes.len(), 1);
+    assert_eq!(screen(&wide), "AB      ");
+}
+
+#[test]
+fn chars_write_a_word_in_one_transaction() {
+    let mut d = display();
+
+    block_on(d.display_chars(['P', 'L', 'A', 'Y'])).unwrap();
+
+    assert_eq!(d.i2c_ref().writes, [vec![0x89, 0, b'P', 0x89, 1, b'L', 0x89, 2, b'A', 0x89, 3, b'Y']]);
+    assert_eq!(d.i2c_ref().writes.len(), transaction_count(Operation::DisplayChars));
+    assert_eq!(screen(&d), "PLAY");
+}
+
+#[test]
+fn chars_reject_a_char_that_cant_be_displayed() {
+    let mut d = display();
+
+    assert!(matches!(block_on(d.display_chars(['P', 'L', 'A', '~'])), Err(Error::UnsupportedChar('~'))));
+    assert!(matches!(block_on(d.display_chars(['€', 'L', 'A', 'Y'])), Err(Error::UnsupportedChar('€'))));
+    assert!(d.i2c_ref().writes.is_empty());
+}
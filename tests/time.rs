@@ -0,0 +1,290 @@
+//! Clock, timer and date layouts
+
+mod common;
+
+use akafugu_twidisplay_async::*;
+use common::*;
+
+/// Dots register value with the dot after position 1 on, the HH.MM separator
+const SEPARATOR: u8 = 0b0000_0100;
+
+#[test]
+fn clock_toggle_shows_hours_and_minutes() {
+    let mut d = display();
+
+    block_on(d.display_clock_toggle(12, 34, 56, false)).unwrap();
+
+    assert_eq!(screen(&d), "1234");
+    assert_eq!(dots(&d), SEPARATOR);
+}
+
+#[test]
+fn clock_toggle_shows_minutes_and_seconds() {
+    let mut d = display();
+
+    block_on(d.display_clock_toggle(12, 34, 56, true)).unwrap();
+
+    assert_eq!(screen(&d), "3456");
+    assert_eq!(dots(&d), SEPARATOR);
+}
+
+#[test]
+fn clock_toggle_rejects_invalid_fields() {
+    let mut d = display();
+
+    for ((hours, minutes, seconds), wrong) in [((24, 0, 0), 24), ((0, 60, 0), 60), ((0, 0, 60), 60)] {
+        assert!(matches!(
+            block_on(d.display_clock_toggle(hours, minutes, seconds, true)),
+            Err(Error::ValueOutOfRange(value)) if value == wrong
+        ));
+    }
+    assert!(d.i2c_ref().writes.is_empty());
+}
+
+#[test]
+fn time_padded_blanks_the_leading_hour_zero() {
+    for hours in 0..10 {
+        let mut d = display();
+
+        block_on(d.display_time_padded(hours, 5, true, false)).unwrap();
+
+        assert_eq!(screen(&d), format!(" {}05", hours));
+        assert_eq!(dots(&d), SEPARATOR);
+        // the zero is never written: one write for the positions, one for the dots
+        assert_eq!(d.i2c_ref().writes.len(), 2);
+        assert_eq!(d.i2c_ref().writes[0][2], b' ');
+    }
+}
+
+#[test]
+fn time_padded_keeps_two_digit_hours_and_the_zero_when_asked() {
+    let mut d = display();
+
+    block_on(d.display_time_padded(10, 5, false, false)).unwrap();
+    assert_eq!(screen(&d), "1005");
+    assert_eq!(dots(&d), 0);
+
+    block_on(d.display_time_padded(9, 5, true, true)).unwrap();
+    assert_eq!(screen(&d), "0905");
+}
+
+/// Positions and values written by the position writes in `writes`
+fn cells(writes: &[Vec<u8>]) -> Vec<(u8, u8)> {
+    writes
+        .iter()
+        .filter(|write| write[0] == 0x89)
+        .flat_map(|write| write.chunks(3).map(|command| (command[1], command[2])))
+        .collect()
+}
+
+#[test]
+fn time_rollover_rewrites_all_four_cells() {
+    let mut d = display();
+
+    block_on(d.display_time(12, 59, true)).unwrap();
+    assert_eq!(screen(&d), "1259");
+    take_writes(&mut d);
+
+    block_on(d.display_time(13, 0, true)).unwrap();
+
+    assert_eq!(cells(&d.i2c_ref().writes), [(0, 1), (1, 3), (2, 0), (3, 0)]);
+    assert_eq!(dots(&d), SEPARATOR);
+}
+
+#[test]
+fn seconds_rollover_rewrites_all_four_cells() {
+    let mut d = display();
+
+    block_on(d.display_duration(12 * 60 + 59)).unwrap();
+    assert_eq!(screen(&d), "1259");
+    take_writes(&mut d);
+
+    block_on(d.display_duration(13 * 60)).unwrap();
+    assert_eq!(cells(&d.i2c_ref().writes), [(0, 1), (1, 3), (2, 0), (3, 0)]);
+    assert_eq!(dots(&d), SEPARATOR);
+
+    block_on(d.display_stopwatch(12, 59, true)).unwrap();
+    take_writes(&mut d);
+    block_on(d.display_stopwatch(13, 0, true)).unwrap();
+    assert_eq!(cells(&d.i2c_ref().writes), [(0, 1), (1, 3), (2, 0), (3, 0)]);
+}
+
+#[test]
+fn seconds_pip_toggles_the_rightmost_dot_only() {
+    let mut d = display();
+    block_on(d.display_time(12, 34, true)).unwrap();
+    take_writes(&mut d);
+
+    block_on(d.seconds_pip(10)).unwrap();
+    assert_eq!(d.i2c_ref().writes, [vec![0x85, SEPARATOR | 0b0001_0000]]);
+
+    block_on(d.seconds_pip(11)).unwrap();
+    assert_eq!(dots(&d), SEPARATOR);
+    assert!(d.i2c_ref().writes.iter().all(|write| write[0] == 0x85));
+}
+
+#[test]
+fn seconds_pip_rejects_invalid_seconds() {
+    let mut d = display();
+
+    assert!(matches!(block_on(d.seconds_pip(60)), Err(Error::ValueOutOfRange(60))));
+    assert!(d.i2c_ref().writes.is_empty());
+}
+
+#[test]
+fn clock_tuple_blinks_the_dot_with_the_seconds() {
+    let mut d = display();
+
+    block_on(d.display_clock_tuple((9, 41, 10), true)).unwrap();
+    assert_eq!(screen(&d), "0941");
+    assert_eq!(dots(&d), SEPARATOR);
+
+    block_on(d.display_clock_tuple((9, 41, 11), true)).unwrap();
+    assert_eq!(dots(&d), 0);
+
+    block_on(d.display_clock_tuple((9, 41, 12), false)).unwrap();
+    assert_eq!(dots(&d), 0);
+}
+
+#[test]
+fn clock_tuple_rejects_invalid_fields() {
+    let mut d = display();
+
+    assert!(matches!(block_on(d.display_clock_tuple((24, 0, 0), true)), Err(Error::ValueOutOfRange(24))));
+    assert!(matches!(block_on(d.display_clock_tuple((12, 60, 0), true)), Err(Error::ValueOutOfRange(60))));
+    assert!(matches!(block_on(d.display_clock_tuple((12, 0, 60), true)), Err(Error::ValueOutOfRange(60))));
+    assert!(d.i2c_ref().writes.is_empty());
+}
+
+#[test]
+fn time_12h_converts_and_marks_pm() {
+    let mut d = display();
+
+    block_on(d.display_time_12h(13, 5, true)).unwrap();
+    assert_eq!(screen(&d), " 105");
+    assert_eq!(dots(&d), SEPARATOR | 0b0001_0000);
+
+    block_on(d.display_time_12h(0, 30, false)).unwrap();
+    assert_eq!(screen(&d), "1230");
+    assert_eq!(dots(&d), 0);
+
+    block_on(d.display_time_12h(12, 0, false)).unwrap();
+    assert_eq!(screen(&d), "1200");
+    assert_eq!(dots(&d), 0b0001_0000);
+}
+
+#[test]
+fn time_12h_rejects_invalid_fields() {
+    let mut d = display();
+
+    assert!(matches!(block_on(d.display_time_12h(24, 0, true)), Err(Error::ValueOutOfRange(24))));
+    assert!(matches!(block_on(d.display_time_12h(12, 60, true)), Err(Error::ValueOutOfRange(60))));
+    assert!(d.i2c_ref().writes.is_empty());
+}
+
+#[test]
+fn stopwatch_goes_up_to_99_59() {
+    let mut d = display();
+
+    block_on(d.display_stopwatch(99, 59, true)).unwrap();
+
+    assert_eq!(screen(&d), "9959");
+    assert_eq!(dots(&d), SEPARATOR);
+}
+
+#[test]
+fn stopwatch_rejects_invalid_fields() {
+    let mut d = display();
+
+    assert!(matches!(block_on(d.display_stopwatch(12, 60, true)), Err(Error::ValueOutOfRange(60))));
+    assert!(matches!(block_on(d.display_stopwatch(100, 0, true)), Err(Error::ValueOutOfRange(100))));
+    assert!(d.i2c_ref().writes.is_empty());
+}
+
+#[test]
+fn countdown_switches_from_minutes_to_hours() {
+    for (total_seconds, expected) in [(90, "0130"), (3599, "5959"), (3600, " 100"), (3661, " 101"), (36000, "1000")] {
+        let mut d = display();
+
+        block_on(d.display_countdown(total_seconds, true)).unwrap();
+
+        assert_eq!(screen(&d), expected, "{} s", total_seconds);
+        assert_eq!(dots(&d), SEPARATOR);
+    }
+}
+
+#[test]
+fn countdown_fits_the_longest_value() {
+    let mut d = display();
+
+    // u16::MAX seconds can't overflow the HH.MM layout
+    block_on(d.display_countdown(u16::MAX, false)).unwrap();
+
+    assert_eq!(screen(&d), "1812");
+    assert_eq!(dots(&d), 0);
+}
+
+#[test]
+fn date_checked_knows_leap_years() {
+    let mut d = display();
+
+    assert!(matches!(
+        block_on(d.display_date_checked(2023, 2, 29, DateFormat::DDMM, true)),
+        Err(Error::DateOutOfRange(2, 29))
+    ));
+    assert!(d.i2c_ref().writes.is_empty());
+
+    block_on(d.display_date_checked(2024, 2, 29, DateFormat::DDMM, true)).unwrap();
+    assert_eq!(screen(&d), "2902");
+    assert_eq!(dots(&d), SEPARATOR);
+
+    // century rules: 1900 isn't a leap year, 2000 is
+    assert!(block_on(d.display_date_checked(1900, 2, 29, DateFormat::MMDD, false)).is_err());
+    block_on(d.display_date_checked(2000, 2, 29, DateFormat::MMDD, false)).unwrap();
+    assert_eq!(screen(&d), "0229");
+}
+
+#[test]
+fn year_shows_four_digits_without_dots() {
+    let mut d = display();
+    block_on(d.display_dots([true; 4])).unwrap();
+
+    block_on(d.display_year(2024)).unwrap();
+    assert_eq!(screen(&d), "2024");
+    assert_eq!(dots(&d), 0);
+
+    block_on(d.display_year(9999)).unwrap();
+    assert_eq!(screen(&d), "9999");
+
+    take_writes(&mut d);
+    assert!(matches!(block_on(d.display_year(10000)), Err(Error::NumberTooLarge(10000))));
+    assert!(d.i2c_ref().writes.is_empty());
+}
+
+#[test]
+fn time_native_sends_hours_and_minutes() {
+    let mut d = display();
+
+    block_on(d.display_time_native(12, 34)).unwrap();
+    assert_eq!(d.i2c_ref().writes, [vec![0x87, 12, 34]]);
+
+    assert!(matches!(block_on(d.display_time_native(24, 0)), Err(Error::ValueOutOfRange(24))));
+    assert!(matches!(block_on(d.display_time_native(12, 60)), Err(Error::ValueOutOfRange(60))));
+    assert_eq!(d.i2c_ref().writes.len(), 1);
+}
+
+#[test]
+fn duration_picks_the_format_from_the_length() {
+    let cases = [(45, " 45S", 0), (300, "0500", SEPARATOR), (7200, " 200", SEPARATOR), (200_000, "  2d", 0)];
+
+    for (seconds, expected, dot) in cases {
+        let mut d = display();
+
+        block_on(d.display_duration(seconds)).unwrap();
+
+        assert_eq!(screen(&d), expected, "{} s", seconds);
+        assert_eq!(dots(&d), dot, "{} s", seconds);
+    }
+
+    assert!(matches!(block_on(display().display_duration(86_400_000)), Err(Error::NumberTooLarge(86_400_000))));
+}